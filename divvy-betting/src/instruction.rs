@@ -1,18 +1,31 @@
-use solana_program::program_error::ProgramError;
-use std::convert::TryInto;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use arrayref::{array_ref, array_refs};
+use std::{convert::TryInto, mem::size_of};
 
 use crate::{
     error::ExchangeError::{self, InvalidInstruction},
-    state::BetType,
+    schema::token_program_id,
+    state::{BetType, FeedChecks, MarketMetadata, ResultOracle, StakeLimits, TeaserConfig},
+    event_group_address, user_exposure_address,
 };
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum ExchangeInstruction {
     Initbet {
         risk: u64,
-        odds: u64,
-        points: u16,
+        /// The American odds quoted to the bettor.
+        odds: i64,
+        /// Line of the bet in hundredths of a point, see `POINTS_SCALE`. Zero on moneyline bets.
+        points: i32,
         market_side: u8,
         bet_type: BetType,
+        /// Maximum payout reduction, in basis points, the bettor accepts
+        /// if the feed odds moved against them since `odds` was quoted.
+        max_slippage_bps: u16,
         bump_seed: u8
     },
     SettleBet {
@@ -22,9 +35,19 @@ pub enum ExchangeInstruction {
         bump_seed: u8,
     },
     InitMarket {
+        /// Unix timestamp at which the event starts and betting closes.
+        start_timestamp: i64,
+        /// 2 for events without a draw, 3 for events with one.
+        side_count: u8,
+        metadata: MarketMetadata,
         bump_seed: u8
     },
+    /// Creates a futures market with `side_count` sides. The odds feeds passed
+    /// along are bound to the sides in order, the rest can be added with
+    /// `AddFuturesSides`.
     InitFuturesMarket {
+        start_timestamp: i64,
+        side_count: u8,
         bump_seed: u8
     },
     SettleMarket {
@@ -46,7 +69,254 @@ pub enum ExchangeInstruction {
         multiplier: u32,
         risk: u16,
     },
-    SettleBustBet {}
+    SettleBustBet {},
+    VoidMarket {
+        bump_seed: u8,
+    },
+    RefundBet {
+        bump_seed: u8,
+    },
+    AddFuturesSides {
+        bump_seed: u8,
+    },
+    InitFuturesBet {
+        risk: u64,
+        odds: i64,
+        market_side: u8,
+        max_slippage_bps: u16,
+        bump_seed: u8,
+    },
+    CommenceFuturesMarket {
+        bump_seed: u8,
+    },
+    SettleFuturesMarket {
+        bump_seed: u8,
+    },
+    SettleFuturesBet {
+        bump_seed: u8,
+    },
+    SettleFuturesPNL {
+        bump_seed: u8,
+    },
+    /// Closes a settled or voided market once all of its bets are settled and
+    /// returns the rent to the authority.
+    CloseMarket {
+        bump_seed: u8,
+    },
+    /// Replaces the result recorded by `SettleMarket` or `SettleFuturesMarket`
+    /// while its dispute window is open. Futures markets ignore the scores.
+    /// Authority only.
+    OverrideResult {
+        winning_side: u8,
+        team_a_score: u16,
+        team_b_score: u16,
+        bump_seed: u8,
+    },
+    /// Replaces the extra result oracles of a market with the result, team A
+    /// score and team B score feeds passed in, three accounts per oracle, and
+    /// sets how many oracles must agree before the market settles.
+    ConfigureResultOracles {
+        result_quorum: u8,
+        bump_seed: u8,
+    },
+    /// Sets the staleness and oracle response thresholds of a moneyline or
+    /// futures market, and how far bet lines may stray from the points feeds.
+    /// Authority only.
+    ConfigureFeedChecks {
+        feed_checks: FeedChecks,
+        bump_seed: u8,
+    },
+    /// Designates the key allowed to settle a market by hand and how long
+    /// after commencement it has to wait. Authority only.
+    ConfigureManualSettlement {
+        grace_period: i64,
+        bump_seed: u8,
+    },
+    /// Settles a market from a result posted by its manual settler, for when
+    /// the result feeds stopped updating.
+    ManualSettleMarket {
+        winning_side: u8,
+        team_a_score: u16,
+        team_b_score: u16,
+        bump_seed: u8,
+    },
+    /// Sets the stake limits of a moneyline or futures market. Authority only.
+    ConfigureStakeLimits {
+        stake_limits: StakeLimits,
+        bump_seed: u8,
+    },
+    /// Sets the largest share of the house liquidity a single market and a
+    /// single event may lock, in basis points. Authority only.
+    ConfigureLiquidityCeilings {
+        max_market_liquidity_bps: u16,
+        max_event_liquidity_bps: u16,
+    },
+    /// Stops new bets on a moneyline or futures market, e.g. while news
+    /// moves the odds. Settlement and other markets are not affected.
+    /// Authority only.
+    SuspendMarket {
+        bump_seed: u8,
+    },
+    /// Lifts a `SuspendMarket`. Authority only.
+    ResumeMarket {
+        bump_seed: u8,
+    },
+    /// Turns betting after commencement on or off for a moneyline market.
+    /// When turning it on, the live odds feeds follow, one per market side.
+    /// Authority only.
+    ConfigureLiveBetting {
+        live_betting: bool,
+        /// Slots a live bet waits before it can be accepted.
+        acceptance_delay_slots: u64,
+        bump_seed: u8,
+    },
+    /// Books a live bet once its acceptance delay has passed, or refunds it if
+    /// the live odds shortened in the meantime, the market was suspended or
+    /// the house can no longer cover it within the liquidity ceilings.
+    AcceptLiveBet {
+        bump_seed: u8,
+    },
+    /// Places a bet on several markets at once. Each leg's market and odds
+    /// feed follow the fixed accounts, along with the points feed of the
+    /// leg's side for legs other than moneyline.
+    InitParlay {
+        risk: u64,
+        /// Maximum payout reduction, in basis points, the bettor accepts
+        /// if the combined feed odds moved against them.
+        max_slippage_bps: u16,
+        legs: Vec<ParlayLegQuote>,
+        bump_seed: u8,
+    },
+    /// Settles the legs of a parlay whose markets have a result, and the
+    /// parlay itself once a leg lost or every leg is settled. Anyone can send it.
+    SettleParlay {
+        bump_seed: u8,
+    },
+    /// Creates the terms of a teaser: how many points every leg's line moves
+    /// in the bettor's favour and the American odds paid for each leg count.
+    /// The terms cannot change once created. Authority only.
+    InitTeaserConfig {
+        /// In hundredths of a point.
+        teaser_points: i32,
+        /// Odds for 2 up to `Parlay::MAX_LEGS` legs, 0 where that count is not offered.
+        odds: [i64; TeaserConfig::ODDS_LEN],
+    },
+    /// Places a teaser, a parlay of spread and total legs whose lines are
+    /// moved by the config's points and paid from its odds table. The teaser
+    /// config follows the fixed accounts, then each leg's market and the
+    /// points feed of the leg's side. Settled with `SettleParlay`.
+    InitTeaser {
+        risk: u64,
+        legs: Vec<TeaserLegQuote>,
+        bump_seed: u8,
+    },
+    /// Turns cash out on or off for a moneyline market and sets the margin
+    /// taken off the fair value of cashed out bets. Authority only.
+    ConfigureCashOut {
+        cash_out: bool,
+        cash_out_margin_bps: u16,
+        bump_seed: u8,
+    },
+    /// Sells a pending bet back to the house at the current odds of its side,
+    /// before commencement or in play on live markets. The bet leaves its
+    /// market and the liquidity it no longer needs returns to the house pool.
+    CashOut {
+        /// Least the bettor accepts for the bet.
+        min_value: u64,
+        bump_seed: u8,
+    },
+    /// Voids a futures market that has no result yet and returns its locked
+    /// liquidity to the house pool. Authority only.
+    VoidFuturesMarket {
+        bump_seed: u8,
+    },
+    /// Refunds the risk of a bet on a voided futures market.
+    RefundFuturesBet {
+        bump_seed: u8,
+    },
+    /// Copies a betting pool state account of the legacy size into a new,
+    /// uninitialized account of the current size, then clears the old one
+    /// and returns its rent to the authority. Authority only.
+    MigrateBettingPool,
+    /// Copies a market account of the legacy size into a new, uninitialized
+    /// account of the current size, then clears the old one and returns its
+    /// rent to the authority. Bets point at their market's address, so the
+    /// market must have no pending bets. Authority only.
+    MigrateMarket,
+}
+
+/// One leg of an `InitParlay` instruction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParlayLegQuote {
+    /// The American odds quoted to the bettor for this leg.
+    pub odds: i64,
+    pub points: i32,
+    pub market_side: u8,
+    pub bet_type: BetType,
+}
+
+impl ParlayLegQuote {
+    const LEN: usize = 14;
+
+    fn unpack(input: &[u8; ParlayLegQuote::LEN]) -> Result<Self, ProgramError> {
+        let (odds, points, market_side, bet_type) = array_refs![input, 8, 4, 1, 1];
+        Ok(ParlayLegQuote {
+            odds: i64::from_le_bytes(*odds),
+            points: i32::from_le_bytes(*points),
+            market_side: market_side[0],
+            bet_type: BetType::unpack(&bet_type[0])?,
+        })
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.odds.to_le_bytes());
+        buf.extend_from_slice(&self.points.to_le_bytes());
+        buf.push(self.market_side);
+        buf.push(self.bet_type.pack());
+    }
+}
+
+/// One leg of an `InitTeaser` instruction, at the line before it is teased.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TeaserLegQuote {
+    pub points: i32,
+    pub market_side: u8,
+    pub bet_type: BetType,
+}
+
+impl TeaserLegQuote {
+    const LEN: usize = 6;
+
+    fn unpack(input: &[u8; TeaserLegQuote::LEN]) -> Result<Self, ProgramError> {
+        let (points, market_side, bet_type) = array_refs![input, 4, 1, 1];
+        Ok(TeaserLegQuote {
+            points: i32::from_le_bytes(*points),
+            market_side: market_side[0],
+            bet_type: BetType::unpack(&bet_type[0])?,
+        })
+    }
+
+    fn pack(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.points.to_le_bytes());
+        buf.push(self.market_side);
+        buf.push(self.bet_type.pack());
+    }
+}
+
+/// Accounts of one teaser leg, see `init_teaser`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TeaserLegAccounts {
+    pub market_state_account: Pubkey,
+    pub points_feed_account: Pubkey,
+}
+
+/// Accounts of one parlay leg, see `init_parlay`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParlayLegAccounts {
+    pub market_state_account: Pubkey,
+    pub odds_feed_account: Pubkey,
+    /// Points feed of the leg's side, for legs other than moneyline.
+    pub points_feed_account: Option<Pubkey>,
 }
 
 impl ExchangeInstruction {
@@ -59,7 +329,8 @@ impl ExchangeInstruction {
                     odds: Self::unpack_odds(rest)?,
                     points: Self::unpack_points(rest)?,
                     market_side: Self::unpack_market_side(rest)?,
-                    bet_type: BetType::unpack(&rest[19])?,
+                    bet_type: BetType::unpack(rest.get(21).ok_or(InvalidInstruction)?)?,
+                    max_slippage_bps: Self::unpack_slippage(rest)?,
                     bump_seed: Self::unpack_last(rest)?,
                 }
             }
@@ -68,6 +339,13 @@ impl ExchangeInstruction {
             },
             2 => {
                 Self::InitMarket {
+                    start_timestamp: Self::unpack_timestamp(rest)?,
+                    side_count: *rest.get(8).ok_or(InvalidInstruction)?,
+                    metadata: rest
+                        .get(9..9 + MarketMetadata::LEN)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(MarketMetadata::unpack)
+                        .ok_or(InvalidInstruction)?,
                     bump_seed: Self::unpack_last(rest)?,
                 }
             }
@@ -92,6 +370,8 @@ impl ExchangeInstruction {
                 bump_seed: Self::unpack_last(rest)?,
             },
             8 => Self::InitFuturesMarket {
+                start_timestamp: Self::unpack_timestamp(rest)?,
+                side_count: *rest.get(8).ok_or(InvalidInstruction)?,
                 bump_seed: Self::unpack_last(rest)?,
             },
             9 => Self::InitBust {
@@ -103,10 +383,490 @@ impl ExchangeInstruction {
             },
             11 => Self::SettleBustBet {
             },
+            12 => Self::VoidMarket {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            13 => Self::RefundBet {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            14 => Self::AddFuturesSides {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            15 => Self::InitFuturesBet {
+                risk: Self::unpack_amount(rest)?,
+                odds: Self::unpack_odds(rest)?,
+                market_side: *rest.get(16).ok_or(InvalidInstruction)?,
+                max_slippage_bps: rest
+                    .get(17..19)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?,
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            16 => Self::CommenceFuturesMarket {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            17 => Self::SettleFuturesMarket {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            18 => Self::SettleFuturesBet {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            19 => Self::SettleFuturesPNL {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            20 => Self::CloseMarket {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            21 => {
+                let (winning_side, team_a_score, team_b_score) = Self::unpack_result(rest)?;
+                Self::OverrideResult {
+                    winning_side,
+                    team_a_score,
+                    team_b_score,
+                    bump_seed: Self::unpack_last(rest)?,
+                }
+            }
+            22 => Self::ConfigureResultOracles {
+                result_quorum: *rest.first().ok_or(InvalidInstruction)?,
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            23 => Self::ConfigureFeedChecks {
+                feed_checks: rest
+                    .get(..FeedChecks::LEN)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(FeedChecks::unpack)
+                    .ok_or(InvalidInstruction)?,
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            24 => Self::ConfigureManualSettlement {
+                grace_period: Self::unpack_timestamp(rest)?,
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            25 => {
+                let (winning_side, team_a_score, team_b_score) = Self::unpack_result(rest)?;
+                Self::ManualSettleMarket {
+                    winning_side,
+                    team_a_score,
+                    team_b_score,
+                    bump_seed: Self::unpack_last(rest)?,
+                }
+            }
+            26 => Self::ConfigureStakeLimits {
+                stake_limits: rest
+                    .get(..StakeLimits::LEN)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(StakeLimits::unpack)
+                    .ok_or(InvalidInstruction)?,
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            27 => {
+                let bps = |range: std::ops::Range<usize>| {
+                    rest.get(range)
+                        .and_then(|slice| slice.try_into().ok())
+                        .map(u16::from_le_bytes)
+                        .ok_or(InvalidInstruction)
+                };
+                Self::ConfigureLiquidityCeilings {
+                    max_market_liquidity_bps: bps(0..2)?,
+                    max_event_liquidity_bps: bps(2..4)?,
+                }
+            }
+            28 => Self::SuspendMarket {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            29 => Self::ResumeMarket {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            30 => {
+                let (live_betting, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                Self::ConfigureLiveBetting {
+                    live_betting: *live_betting != 0,
+                    acceptance_delay_slots: Self::unpack_amount(rest)?,
+                    bump_seed: Self::unpack_last(rest)?,
+                }
+            }
+            31 => Self::AcceptLiveBet {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            32 => {
+                let max_slippage_bps = rest
+                    .get(8..10)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                let leg_count = *rest.get(10).ok_or(InvalidInstruction)? as usize;
+                let legs_end = 11 + leg_count * ParlayLegQuote::LEN;
+                let legs = rest
+                    .get(11..legs_end)
+                    .ok_or(InvalidInstruction)?
+                    .chunks_exact(ParlayLegQuote::LEN)
+                    .map(|leg| ParlayLegQuote::unpack(array_ref![leg, 0, ParlayLegQuote::LEN]))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if rest.len() != legs_end + 1 {
+                    return Err(InvalidInstruction.into());
+                }
+                Self::InitParlay {
+                    risk: Self::unpack_amount(rest)?,
+                    max_slippage_bps,
+                    legs,
+                    bump_seed: Self::unpack_last(rest)?,
+                }
+            }
+            33 => Self::SettleParlay {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            34 => {
+                let teaser_points = rest
+                    .get(..4)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(i32::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                let odds_src = rest
+                    .get(4..)
+                    .filter(|slice| slice.len() == 8 * TeaserConfig::ODDS_LEN)
+                    .ok_or(InvalidInstruction)?;
+                let mut odds = [0; TeaserConfig::ODDS_LEN];
+                for (odds, src) in odds.iter_mut().zip(odds_src.chunks_exact(8)) {
+                    *odds = i64::from_le_bytes(*array_ref![src, 0, 8]);
+                }
+                Self::InitTeaserConfig { teaser_points, odds }
+            }
+            35 => {
+                let leg_count = *rest.get(8).ok_or(InvalidInstruction)? as usize;
+                let legs_end = 9 + leg_count * TeaserLegQuote::LEN;
+                let legs = rest
+                    .get(9..legs_end)
+                    .ok_or(InvalidInstruction)?
+                    .chunks_exact(TeaserLegQuote::LEN)
+                    .map(|leg| TeaserLegQuote::unpack(array_ref![leg, 0, TeaserLegQuote::LEN]))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if rest.len() != legs_end + 1 {
+                    return Err(InvalidInstruction.into());
+                }
+                Self::InitTeaser {
+                    risk: Self::unpack_amount(rest)?,
+                    legs,
+                    bump_seed: Self::unpack_last(rest)?,
+                }
+            }
+            36 => {
+                let (cash_out, rest) = rest.split_first().ok_or(InvalidInstruction)?;
+                let cash_out_margin_bps = rest
+                    .get(..2)
+                    .and_then(|slice| slice.try_into().ok())
+                    .map(u16::from_le_bytes)
+                    .ok_or(InvalidInstruction)?;
+                Self::ConfigureCashOut {
+                    cash_out: *cash_out != 0,
+                    cash_out_margin_bps,
+                    bump_seed: Self::unpack_last(rest)?,
+                }
+            }
+            37 => Self::CashOut {
+                min_value: Self::unpack_amount(rest)?,
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            38 => Self::VoidFuturesMarket {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            39 => Self::RefundFuturesBet {
+                bump_seed: Self::unpack_last(rest)?,
+            },
+            40 => Self::MigrateBettingPool,
+            41 => Self::MigrateMarket,
             _ => return Err(InvalidInstruction.into()),
         })
     }
 
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(size_of::<Self>());
+        match *self {
+            Self::Initbet {
+                risk,
+                odds,
+                points,
+                market_side,
+                bet_type,
+                max_slippage_bps,
+                bump_seed,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&risk.to_le_bytes());
+                buf.extend_from_slice(&odds.to_le_bytes());
+                buf.extend_from_slice(&points.to_le_bytes());
+                buf.push(market_side);
+                buf.push(bet_type.pack());
+                buf.extend_from_slice(&max_slippage_bps.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::SettleBet { bump_seed } => {
+                buf.push(1);
+                buf.push(bump_seed);
+            }
+            Self::InitMarket {
+                start_timestamp,
+                side_count,
+                ref metadata,
+                bump_seed,
+            } => {
+                buf.push(2);
+                buf.extend_from_slice(&start_timestamp.to_le_bytes());
+                buf.push(side_count);
+                let mut metadata_buf = [0u8; MarketMetadata::LEN];
+                metadata.pack(&mut metadata_buf);
+                buf.extend_from_slice(&metadata_buf);
+                buf.push(bump_seed);
+            }
+            Self::SettleMarket { bump_seed } => {
+                buf.push(3);
+                buf.push(bump_seed);
+            }
+            Self::Ownership { bump_seed } => {
+                buf.push(4);
+                buf.push(bump_seed);
+            }
+            Self::CommenceMarket { bump_seed } => {
+                buf.push(5);
+                buf.push(bump_seed);
+            }
+            Self::Freeze { freeze_betting } => {
+                buf.push(6);
+                buf.push(freeze_betting as u8);
+            }
+            Self::SettlePNL { bump_seed } => {
+                buf.push(7);
+                buf.push(bump_seed);
+            }
+            Self::InitFuturesMarket {
+                start_timestamp,
+                side_count,
+                bump_seed,
+            } => {
+                buf.push(8);
+                buf.extend_from_slice(&start_timestamp.to_le_bytes());
+                buf.push(side_count);
+                buf.push(bump_seed);
+            }
+            Self::InitBust { multiplier } => {
+                buf.push(9);
+                buf.extend_from_slice(&multiplier.to_le_bytes());
+            }
+            Self::InitBustBet { multiplier, risk } => {
+                buf.push(10);
+                buf.extend_from_slice(&multiplier.to_le_bytes());
+                buf.extend_from_slice(&risk.to_le_bytes());
+            }
+            Self::SettleBustBet {} => {
+                buf.push(11);
+            }
+            Self::VoidMarket { bump_seed } => {
+                buf.push(12);
+                buf.push(bump_seed);
+            }
+            Self::RefundBet { bump_seed } => {
+                buf.push(13);
+                buf.push(bump_seed);
+            }
+            Self::AddFuturesSides { bump_seed } => {
+                buf.push(14);
+                buf.push(bump_seed);
+            }
+            Self::InitFuturesBet {
+                risk,
+                odds,
+                market_side,
+                max_slippage_bps,
+                bump_seed,
+            } => {
+                buf.push(15);
+                buf.extend_from_slice(&risk.to_le_bytes());
+                buf.extend_from_slice(&odds.to_le_bytes());
+                buf.push(market_side);
+                buf.extend_from_slice(&max_slippage_bps.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::CommenceFuturesMarket { bump_seed } => {
+                buf.push(16);
+                buf.push(bump_seed);
+            }
+            Self::SettleFuturesMarket { bump_seed } => {
+                buf.push(17);
+                buf.push(bump_seed);
+            }
+            Self::SettleFuturesBet { bump_seed } => {
+                buf.push(18);
+                buf.push(bump_seed);
+            }
+            Self::SettleFuturesPNL { bump_seed } => {
+                buf.push(19);
+                buf.push(bump_seed);
+            }
+            Self::CloseMarket { bump_seed } => {
+                buf.push(20);
+                buf.push(bump_seed);
+            }
+            Self::OverrideResult {
+                winning_side,
+                team_a_score,
+                team_b_score,
+                bump_seed,
+            } => {
+                buf.push(21);
+                buf.push(winning_side);
+                buf.extend_from_slice(&team_a_score.to_le_bytes());
+                buf.extend_from_slice(&team_b_score.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::ConfigureResultOracles {
+                result_quorum,
+                bump_seed,
+            } => {
+                buf.push(22);
+                buf.push(result_quorum);
+                buf.push(bump_seed);
+            }
+            Self::ConfigureFeedChecks {
+                ref feed_checks,
+                bump_seed,
+            } => {
+                buf.push(23);
+                let mut feed_checks_buf = [0u8; FeedChecks::LEN];
+                feed_checks.pack(&mut feed_checks_buf);
+                buf.extend_from_slice(&feed_checks_buf);
+                buf.push(bump_seed);
+            }
+            Self::ConfigureManualSettlement {
+                grace_period,
+                bump_seed,
+            } => {
+                buf.push(24);
+                buf.extend_from_slice(&grace_period.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::ManualSettleMarket {
+                winning_side,
+                team_a_score,
+                team_b_score,
+                bump_seed,
+            } => {
+                buf.push(25);
+                buf.push(winning_side);
+                buf.extend_from_slice(&team_a_score.to_le_bytes());
+                buf.extend_from_slice(&team_b_score.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::ConfigureStakeLimits {
+                ref stake_limits,
+                bump_seed,
+            } => {
+                buf.push(26);
+                let mut stake_limits_buf = [0u8; StakeLimits::LEN];
+                stake_limits.pack(&mut stake_limits_buf);
+                buf.extend_from_slice(&stake_limits_buf);
+                buf.push(bump_seed);
+            }
+            Self::ConfigureLiquidityCeilings {
+                max_market_liquidity_bps,
+                max_event_liquidity_bps,
+            } => {
+                buf.push(27);
+                buf.extend_from_slice(&max_market_liquidity_bps.to_le_bytes());
+                buf.extend_from_slice(&max_event_liquidity_bps.to_le_bytes());
+            }
+            Self::SuspendMarket { bump_seed } => {
+                buf.push(28);
+                buf.push(bump_seed);
+            }
+            Self::ResumeMarket { bump_seed } => {
+                buf.push(29);
+                buf.push(bump_seed);
+            }
+            Self::ConfigureLiveBetting {
+                live_betting,
+                acceptance_delay_slots,
+                bump_seed,
+            } => {
+                buf.push(30);
+                buf.push(live_betting as u8);
+                buf.extend_from_slice(&acceptance_delay_slots.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::AcceptLiveBet { bump_seed } => {
+                buf.push(31);
+                buf.push(bump_seed);
+            }
+            Self::InitParlay {
+                risk,
+                max_slippage_bps,
+                ref legs,
+                bump_seed,
+            } => {
+                buf.push(32);
+                buf.extend_from_slice(&risk.to_le_bytes());
+                buf.extend_from_slice(&max_slippage_bps.to_le_bytes());
+                buf.push(legs.len() as u8);
+                for leg in legs.iter() {
+                    leg.pack(&mut buf);
+                }
+                buf.push(bump_seed);
+            }
+            Self::SettleParlay { bump_seed } => {
+                buf.push(33);
+                buf.push(bump_seed);
+            }
+            Self::InitTeaserConfig {
+                teaser_points,
+                ref odds,
+            } => {
+                buf.push(34);
+                buf.extend_from_slice(&teaser_points.to_le_bytes());
+                for odds in odds.iter() {
+                    buf.extend_from_slice(&odds.to_le_bytes());
+                }
+            }
+            Self::InitTeaser {
+                risk,
+                ref legs,
+                bump_seed,
+            } => {
+                buf.push(35);
+                buf.extend_from_slice(&risk.to_le_bytes());
+                buf.push(legs.len() as u8);
+                for leg in legs.iter() {
+                    leg.pack(&mut buf);
+                }
+                buf.push(bump_seed);
+            }
+            Self::ConfigureCashOut {
+                cash_out,
+                cash_out_margin_bps,
+                bump_seed,
+            } => {
+                buf.push(36);
+                buf.push(cash_out as u8);
+                buf.extend_from_slice(&cash_out_margin_bps.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::CashOut { min_value, bump_seed } => {
+                buf.push(37);
+                buf.extend_from_slice(&min_value.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::VoidFuturesMarket { bump_seed } => {
+                buf.push(38);
+                buf.push(bump_seed);
+            }
+            Self::RefundFuturesBet { bump_seed } => {
+                buf.push(39);
+                buf.push(bump_seed);
+            }
+            Self::MigrateBettingPool => buf.push(40),
+            Self::MigrateMarket => buf.push(41),
+        };
+        buf
+    }
+
     // Todo: delete these 4 methods and use split_first, like in spl-token/instruction.rs
     fn unpack_last(input: &[u8]) -> Result<u8, ProgramError> {
         let (last, _rest) = input.split_last().ok_or(InvalidInstruction)?;
@@ -125,13 +885,35 @@ impl ExchangeInstruction {
 
     fn unpack_bust_risk(input: &[u8]) -> Result<u16, ProgramError> {
         let amount = input
-            .get(5..6)
+            .get(4..6)
             .and_then(|slice| slice.try_into().ok())
             .map(u16::from_le_bytes)
             .ok_or(InvalidInstruction)?;
         Ok(amount)
     }
 
+    /// Unpacks a winning side followed by the team A and team B scores.
+    fn unpack_result(input: &[u8]) -> Result<(u8, u16, u16), ProgramError> {
+        let score = |range: std::ops::Range<usize>| {
+            input
+                .get(range)
+                .and_then(|slice| slice.try_into().ok())
+                .map(u16::from_le_bytes)
+                .ok_or(InvalidInstruction)
+        };
+        let winning_side = *input.first().ok_or(InvalidInstruction)?;
+        Ok((winning_side, score(1..3)?, score(3..5)?))
+    }
+
+    fn unpack_timestamp(input: &[u8]) -> Result<i64, ProgramError> {
+        let timestamp = input
+            .get(..8)
+            .and_then(|slice| slice.try_into().ok())
+            .map(i64::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(timestamp)
+    }
+
     fn unpack_amount(input: &[u8]) -> Result<u64, ProgramError> {
         let amount = input
             .get(..8)
@@ -140,28 +922,1587 @@ impl ExchangeInstruction {
             .ok_or(InvalidInstruction)?;
         Ok(amount)
     }
-    fn unpack_odds(input: &[u8]) -> Result<u64, ProgramError> {
+    fn unpack_odds(input: &[u8]) -> Result<i64, ProgramError> {
         let odds = input
             .get(8..16)
             .and_then(|slice| slice.try_into().ok())
-            .map(u64::from_le_bytes)
+            .map(i64::from_le_bytes)
             .ok_or(InvalidInstruction)?;
         Ok(odds)
     }
-    fn unpack_points(input: &[u8]) -> Result<u16, ProgramError> {
+    fn unpack_points(input: &[u8]) -> Result<i32, ProgramError> {
         let points = input
-            .get(16..18)
+            .get(16..20)
             .and_then(|slice| slice.try_into().ok())
-            .map(u16::from_le_bytes)
+            .map(i32::from_le_bytes)
             .ok_or(InvalidInstruction)?;
         Ok(points)
     }
     fn unpack_market_side(input: &[u8]) -> Result<u8, ProgramError> {
         let market_side = input
-            .get(18..19)
+            .get(20..21)
             .and_then(|slice| slice.try_into().ok())
             .map(u8::from_le_bytes)
             .ok_or(InvalidInstruction)?;
         Ok(market_side)
     }
+    fn unpack_slippage(input: &[u8]) -> Result<u16, ProgramError> {
+        let max_slippage_bps = input
+            .get(22..24)
+            .and_then(|slice| slice.try_into().ok())
+            .map(u16::from_le_bytes)
+            .ok_or(InvalidInstruction)?;
+        Ok(max_slippage_bps)
+    }
+}
+
+/// Creates an `Initbet` instruction.
+///
+/// `house_pda_account` and `house_pool_state_account` belong to the divvy house
+/// program and are forwarded to it when locked liquidity has to be moved.
+/// The user's exposure account on the market, at `user_exposure_address`, must
+/// already exist and be owned by the program, as must the group account of the
+/// market's `event_id`, at `event_group_address`.
+///
+/// On a commenced market with live betting, `odds_feed_account` is the live
+/// odds feed of `market_side` and the bet waits for `AcceptLiveBet`.
+///
+/// Bets other than moneyline pass the points feed of `market_side` as
+/// `points_feed_account`, and `points` must match the line it reports.
+#[allow(clippy::too_many_arguments)]
+pub fn init_bet(
+    program_id: &Pubkey,
+    user_main_account: &Pubkey,
+    odds_feed_account: &Pubkey,
+    points_feed_account: Option<&Pubkey>,
+    bet_account: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    house_pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    house_pool_state_account: &Pubkey,
+    divvy_house_program: &Pubkey,
+    event_id: &str,
+    risk: u64,
+    odds: i64,
+    points: i32,
+    market_side: u8,
+    bet_type: BetType,
+    max_slippage_bps: u16,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    if (bet_type == BetType::MoneyLine) != points_feed_account.is_none() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let user_exposure_account =
+        user_exposure_address(user_main_account, market_state_account, program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+    let event_group_account =
+        event_group_address(event_id, program_id).map_err(|_| ProgramError::InvalidSeeds)?;
+    let data = ExchangeInstruction::Initbet {
+        risk,
+        odds,
+        points,
+        market_side,
+        bet_type,
+        max_slippage_bps,
+        bump_seed,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new(*user_main_account, true),
+        AccountMeta::new_readonly(*odds_feed_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*house_pda_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*house_pool_state_account, false),
+        AccountMeta::new_readonly(*divvy_house_program, false),
+        AccountMeta::new(user_exposure_account, false),
+        AccountMeta::new(event_group_account, false),
+    ];
+    if let Some(points_feed_account) = points_feed_account {
+        accounts.push(AccountMeta::new_readonly(*points_feed_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates the divvy house `TransferLockedLiquidity` instruction the program
+/// invokes, signed by `bet_pda_account`, when a market needs more locked
+/// liquidity. Builders of instructions that can trigger it must pass every
+/// account here with at least the same privileges.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_locked_liquidity(
+    divvy_house_program: &Pubkey,
+    token_program: &Pubkey,
+    house_pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    house_pool_state_account: &Pubkey,
+    usdt_amount: u64,
+    bump_seed: u8,
+) -> Instruction {
+    let mut data = Vec::with_capacity(10);
+    data.push(4);
+    data.extend_from_slice(&usdt_amount.to_le_bytes());
+    data.push(bump_seed);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*token_program, false),
+        AccountMeta::new(*house_pda_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, true),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*house_pool_state_account, false),
+    ];
+
+    Instruction {
+        program_id: *divvy_house_program,
+        accounts,
+        data,
+    }
+}
+
+/// Creates a `SettleBet` instruction. The user's exposure account on the
+/// market, at `user_exposure_address`, gets back a live bet that was never
+/// accepted.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_bet(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    user_main_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    insurance_fund_usdt_account: &Pubkey,
+    divvy_foundation_proceeds_usdt_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let user_exposure_account =
+        user_exposure_address(user_main_account, market_state_account, program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+    let data = ExchangeInstruction::SettleBet { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*user_main_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new_readonly(*hp_usdt_account, false),
+        AccountMeta::new_readonly(*insurance_fund_usdt_account, false),
+        AccountMeta::new_readonly(*divvy_foundation_proceeds_usdt_account, false),
+        AccountMeta::new(user_exposure_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettlePNL` instruction. `event_id` is the one in the market's
+/// metadata, its event group gets the market's liquidity back.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_pnl(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    insurance_fund_usdt_account: &Pubkey,
+    divvy_foundation_proceeds_usdt_account: &Pubkey,
+    event_id: &str,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let event_group_account =
+        event_group_address(event_id, program_id).map_err(|_| ProgramError::InvalidSeeds)?;
+    let data = ExchangeInstruction::SettlePNL { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*insurance_fund_usdt_account, false),
+        AccountMeta::new(*divvy_foundation_proceeds_usdt_account, false),
+        AccountMeta::new(event_group_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitMarket` instruction.
+///
+/// `odds_feed_accounts` and `points_feed_accounts` are ordered by market side.
+/// Pass two odds feeds for a two-way market and three for one with a draw.
+/// The points feeds report each side's line, while `score_feed_accounts`
+/// report the team A and team B scores read by `SettleMarket`.
+#[allow(clippy::too_many_arguments)]
+pub fn init_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    result_feed_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    odds_feed_accounts: &[Pubkey],
+    points_feed_accounts: &[Pubkey; 2],
+    score_feed_accounts: &[Pubkey; 2],
+    start_timestamp: i64,
+    metadata: MarketMetadata,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    if odds_feed_accounts.len() < 2 || odds_feed_accounts.len() > 3 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = ExchangeInstruction::InitMarket {
+        start_timestamp,
+        side_count: odds_feed_accounts.len() as u8,
+        metadata,
+        bump_seed,
+    }
+    .pack();
+    let accounts = market_accounts(
+        authority,
+        market_state_account,
+        result_feed_account,
+        bet_pool_state_account,
+        odds_feed_accounts,
+        points_feed_accounts,
+        score_feed_accounts,
+    );
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitFuturesMarket` instruction.
+///
+/// `odds_feed_accounts` are bound to the first sides of the market, in order.
+/// A transaction can only carry a limited number of accounts, so markets with
+/// many sides register the remaining feeds with `add_futures_sides`.
+#[allow(clippy::too_many_arguments)]
+pub fn init_futures_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    futures_market_account: &Pubkey,
+    result_feed_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    odds_feed_accounts: &[Pubkey],
+    side_count: u8,
+    start_timestamp: i64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::InitFuturesMarket {
+        start_timestamp,
+        side_count,
+        bump_seed,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(4 + odds_feed_accounts.len());
+    accounts.push(AccountMeta::new_readonly(*authority, true));
+    accounts.push(AccountMeta::new(*futures_market_account, false));
+    accounts.push(AccountMeta::new_readonly(*result_feed_account, false));
+    accounts.push(AccountMeta::new_readonly(*bet_pool_state_account, false));
+    for odds_feed_account in odds_feed_accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(*odds_feed_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+fn market_accounts(
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    result_feed_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    odds_feed_accounts: &[Pubkey],
+    points_feed_accounts: &[Pubkey; 2],
+    score_feed_accounts: &[Pubkey; 2],
+) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(8 + odds_feed_accounts.len());
+    accounts.push(AccountMeta::new_readonly(*authority, true));
+    accounts.push(AccountMeta::new(*market_state_account, false));
+    accounts.push(AccountMeta::new_readonly(*result_feed_account, false));
+    accounts.push(AccountMeta::new_readonly(*bet_pool_state_account, false));
+    for odds_feed_account in odds_feed_accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(*odds_feed_account, false));
+    }
+    for points_feed_account in points_feed_accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(*points_feed_account, false));
+    }
+    for score_feed_account in score_feed_accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(*score_feed_account, false));
+    }
+    accounts
+}
+
+/// Creates a `SettleMarket` instruction.
+///
+/// `extra_result_oracles` must match the oracles registered on the market, in order.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    result_feed_account: &Pubkey,
+    team_a_score_feed_account: &Pubkey,
+    team_b_score_feed_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    extra_result_oracles: &[ResultOracle],
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleMarket { bump_seed }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new_readonly(*result_feed_account, false),
+        AccountMeta::new_readonly(*team_a_score_feed_account, false),
+        AccountMeta::new_readonly(*team_b_score_feed_account, false),
+        AccountMeta::new_readonly(*hp_usdt_account, false),
+    ];
+    accounts.extend(result_oracle_accounts(extra_result_oracles));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `Ownership` instruction that initializes the betting pool state.
+#[allow(clippy::too_many_arguments)]
+pub fn ownership(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    insurance_fund_usdt_account: &Pubkey,
+    divvy_foundation_proceeds_usdt_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::Ownership { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new_readonly(*hp_usdt_account, false),
+        AccountMeta::new_readonly(*bet_usdt_account, false),
+        AccountMeta::new_readonly(*insurance_fund_usdt_account, false),
+        AccountMeta::new_readonly(*divvy_foundation_proceeds_usdt_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CommenceMarket` instruction.
+///
+/// Before the market's start time only the authority may commence it,
+/// afterwards any signer can.
+pub fn commence_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::CommenceMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Freeze` instruction.
+pub fn freeze(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    freeze_betting: bool,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::Freeze { freeze_betting }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitBust` instruction.
+pub fn init_bust(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    bust_state_account: &Pubkey,
+    current_multiplier_account: &Pubkey,
+    previous_multiplier_account: &Pubkey,
+    multiplier: u32,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::InitBust { multiplier }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*bust_state_account, false),
+        AccountMeta::new(*current_multiplier_account, false),
+        AccountMeta::new(*previous_multiplier_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitBustBet` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn init_bust_bet(
+    program_id: &Pubkey,
+    bust_state_account: &Pubkey,
+    bust_bet_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    multiplier_account: &Pubkey,
+    user_main_account: &Pubkey,
+    multiplier: u32,
+    risk: u16,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::InitBustBet { multiplier, risk }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*bust_state_account, false),
+        AccountMeta::new(*bust_bet_account, false),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*multiplier_account, false),
+        AccountMeta::new_readonly(*user_main_account, true),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleBustBet` instruction.
+pub fn settle_bust_bet(
+    program_id: &Pubkey,
+    bust_bet_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    multiplier_account: &Pubkey,
+    initializer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleBustBet {}.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*bust_bet_account, false),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new_readonly(*multiplier_account, false),
+        AccountMeta::new_readonly(*initializer, true),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `VoidMarket` instruction. `event_id` is the one in the market's
+/// metadata, its event group gets the market's liquidity back.
+#[allow(clippy::too_many_arguments)]
+pub fn void_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    event_id: &str,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let event_group_account =
+        event_group_address(event_id, program_id).map_err(|_| ProgramError::InvalidSeeds)?;
+    let data = ExchangeInstruction::VoidMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(event_group_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RefundBet` instruction for a bet on a voided market.
+#[allow(clippy::too_many_arguments)]
+pub fn refund_bet(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    user_main_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::RefundBet { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*user_main_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `AddFuturesSides` instruction binding `odds_feed_accounts` to
+/// the next sides of a futures market that have no feed yet.
+pub fn add_futures_sides(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    futures_market_account: &Pubkey,
+    odds_feed_accounts: &[Pubkey],
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::AddFuturesSides { bump_seed }.pack();
+
+    let mut accounts = Vec::with_capacity(2 + odds_feed_accounts.len());
+    accounts.push(AccountMeta::new_readonly(*authority, true));
+    accounts.push(AccountMeta::new(*futures_market_account, false));
+    for odds_feed_account in odds_feed_accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(*odds_feed_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitFuturesBet` instruction. The accounts mirror `init_bet`.
+#[allow(clippy::too_many_arguments)]
+pub fn init_futures_bet(
+    program_id: &Pubkey,
+    user_main_account: &Pubkey,
+    odds_feed_account: &Pubkey,
+    bet_account: &Pubkey,
+    futures_market_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    house_pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    house_pool_state_account: &Pubkey,
+    divvy_house_program: &Pubkey,
+    risk: u64,
+    odds: i64,
+    market_side: u8,
+    max_slippage_bps: u16,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let user_exposure_account =
+        user_exposure_address(user_main_account, futures_market_account, program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+    let data = ExchangeInstruction::InitFuturesBet {
+        risk,
+        odds,
+        market_side,
+        max_slippage_bps,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new(*user_main_account, true),
+        AccountMeta::new_readonly(*odds_feed_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new(*futures_market_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*house_pda_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*house_pool_state_account, false),
+        AccountMeta::new_readonly(*divvy_house_program, false),
+        AccountMeta::new(user_exposure_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CommenceFuturesMarket` instruction.
+pub fn commence_futures_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    futures_market_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::CommenceFuturesMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new(*futures_market_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleFuturesMarket` instruction.
+pub fn settle_futures_market(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    futures_market_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    result_feed_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleFuturesMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new(*futures_market_account, false),
+        AccountMeta::new_readonly(*bet_pool_state_account, false),
+        AccountMeta::new_readonly(*result_feed_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleFuturesBet` instruction. The accounts mirror `settle_bet`.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_futures_bet(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    futures_market_account: &Pubkey,
+    bet_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    user_main_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleFuturesBet { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*futures_market_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*user_main_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleFuturesPNL` instruction. The accounts mirror `settle_pnl`.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_futures_pnl(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    futures_market_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    insurance_fund_usdt_account: &Pubkey,
+    divvy_foundation_proceeds_usdt_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleFuturesPNL { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*futures_market_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*insurance_fund_usdt_account, false),
+        AccountMeta::new(*divvy_foundation_proceeds_usdt_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `VoidFuturesMarket` instruction. The accounts mirror
+/// `void_market`, without an event group.
+#[allow(clippy::too_many_arguments)]
+pub fn void_futures_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    futures_market_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::VoidFuturesMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*futures_market_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RefundFuturesBet` instruction. The accounts mirror `refund_bet`.
+#[allow(clippy::too_many_arguments)]
+pub fn refund_futures_bet(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    futures_market_account: &Pubkey,
+    bet_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    user_main_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::RefundFuturesBet { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*futures_market_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*user_main_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CloseMarket` instruction. Works for both moneyline and futures markets.
+pub fn close_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::CloseMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `OverrideResult` instruction.
+pub fn override_result(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    winning_side: u8,
+    team_a_score: u16,
+    team_b_score: u16,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::OverrideResult {
+        winning_side,
+        team_a_score,
+        team_b_score,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ConfigureResultOracles` instruction.
+pub fn configure_result_oracles(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    extra_result_oracles: &[ResultOracle],
+    result_quorum: u8,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ConfigureResultOracles {
+        result_quorum,
+        bump_seed,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+    accounts.extend(result_oracle_accounts(extra_result_oracles));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ConfigureFeedChecks` instruction.
+pub fn configure_feed_checks(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    feed_checks: FeedChecks,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ConfigureFeedChecks {
+        feed_checks,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ConfigureManualSettlement` instruction.
+pub fn configure_manual_settlement(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    manual_settler: &Pubkey,
+    grace_period: i64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ConfigureManualSettlement {
+        grace_period,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new_readonly(*manual_settler, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ManualSettleMarket` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn manual_settle_market(
+    program_id: &Pubkey,
+    manual_settler: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    winning_side: u8,
+    team_a_score: u16,
+    team_b_score: u16,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ManualSettleMarket {
+        winning_side,
+        team_a_score,
+        team_b_score,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*manual_settler, true),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new_readonly(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ConfigureStakeLimits` instruction.
+pub fn configure_stake_limits(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    stake_limits: StakeLimits,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ConfigureStakeLimits {
+        stake_limits,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ConfigureLiquidityCeilings` instruction.
+pub fn configure_liquidity_ceilings(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    max_market_liquidity_bps: u16,
+    max_event_liquidity_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ConfigureLiquidityCeilings {
+        max_market_liquidity_bps,
+        max_event_liquidity_bps,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `MigrateBettingPool` instruction.
+pub fn migrate_betting_pool(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    legacy_bet_pool_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::MigrateBettingPool.pack();
+
+    let accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(*legacy_bet_pool_state_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `MigrateMarket` instruction.
+pub fn migrate_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    legacy_market_state_account: &Pubkey,
+    market_state_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::MigrateMarket.pack();
+
+    let accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(*legacy_market_state_account, false),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SuspendMarket` instruction. Works for both moneyline and futures markets.
+pub fn suspend_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SuspendMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ResumeMarket` instruction. Works for both moneyline and futures markets.
+pub fn resume_market(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ResumeMarket { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ConfigureLiveBetting` instruction. `live_odds_feed_accounts` are
+/// ordered by market side and only read when `live_betting` is set.
+pub fn configure_live_betting(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    live_odds_feed_accounts: &[Pubkey],
+    live_betting: bool,
+    acceptance_delay_slots: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ConfigureLiveBetting {
+        live_betting,
+        acceptance_delay_slots,
+        bump_seed,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+    for live_odds_feed_account in live_odds_feed_accounts.iter() {
+        accounts.push(AccountMeta::new_readonly(*live_odds_feed_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `AcceptLiveBet` instruction. Anyone can send it once the bet's
+/// acceptance delay has passed. `live_odds_feed_account` is the live feed of
+/// the bet's market side and `event_id` the one in the market's metadata.
+/// Bets other than moneyline also pass the points feed of that side as
+/// `points_feed_account`, and are refunded if the line moved.
+#[allow(clippy::too_many_arguments)]
+pub fn accept_live_bet(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    live_odds_feed_account: &Pubkey,
+    points_feed_account: Option<&Pubkey>,
+    bet_account: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    user_main_account: &Pubkey,
+    house_pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    house_pool_state_account: &Pubkey,
+    divvy_house_program: &Pubkey,
+    event_id: &str,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let user_exposure_account =
+        user_exposure_address(user_main_account, market_state_account, program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+    let event_group_account =
+        event_group_address(event_id, program_id).map_err(|_| ProgramError::InvalidSeeds)?;
+    let data = ExchangeInstruction::AcceptLiveBet { bump_seed }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(*live_odds_feed_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*house_pda_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*house_pool_state_account, false),
+        AccountMeta::new_readonly(*divvy_house_program, false),
+        AccountMeta::new(user_exposure_account, false),
+        AccountMeta::new(event_group_account, false),
+        AccountMeta::new(*user_main_account, false),
+    ];
+    if let Some(points_feed_account) = points_feed_account {
+        accounts.push(AccountMeta::new_readonly(*points_feed_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ConfigureCashOut` instruction.
+pub fn configure_cash_out(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    market_state_account: &Pubkey,
+    cash_out: bool,
+    cash_out_margin_bps: u16,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::ConfigureCashOut {
+        cash_out,
+        cash_out_margin_bps,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*market_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CashOut` instruction, signed by the user who placed the bet.
+/// `odds_feed_account` is the odds feed of the bet's market side, the live
+/// one once the market commenced, and `event_id` the one in the market's
+/// metadata. Bets other than moneyline also pass the points feed of that
+/// side as `points_feed_account`, and can only be cashed out while their
+/// line is still offered.
+#[allow(clippy::too_many_arguments)]
+pub fn cash_out(
+    program_id: &Pubkey,
+    user_main_account: &Pubkey,
+    odds_feed_account: &Pubkey,
+    points_feed_account: Option<&Pubkey>,
+    bet_account: &Pubkey,
+    market_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    house_pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    house_pool_state_account: &Pubkey,
+    divvy_house_program: &Pubkey,
+    event_id: &str,
+    min_value: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let user_exposure_account =
+        user_exposure_address(user_main_account, market_state_account, program_id)
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+    let event_group_account =
+        event_group_address(event_id, program_id).map_err(|_| ProgramError::InvalidSeeds)?;
+    let data = ExchangeInstruction::CashOut {
+        min_value,
+        bump_seed,
+    }
+    .pack();
+
+    let mut accounts = vec![
+        AccountMeta::new(*user_main_account, true),
+        AccountMeta::new_readonly(*odds_feed_account, false),
+        AccountMeta::new(*bet_account, false),
+        AccountMeta::new(*market_state_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*house_pda_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*house_pool_state_account, false),
+        AccountMeta::new_readonly(*divvy_house_program, false),
+        AccountMeta::new(user_exposure_account, false),
+        AccountMeta::new(event_group_account, false),
+    ];
+    if let Some(points_feed_account) = points_feed_account {
+        accounts.push(AccountMeta::new_readonly(*points_feed_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitParlay` instruction. `legs` and `leg_accounts` are in the
+/// same order, one entry per leg.
+#[allow(clippy::too_many_arguments)]
+pub fn init_parlay(
+    program_id: &Pubkey,
+    user_main_account: &Pubkey,
+    parlay_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    house_pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    house_pool_state_account: &Pubkey,
+    divvy_house_program: &Pubkey,
+    leg_accounts: &[ParlayLegAccounts],
+    legs: &[ParlayLegQuote],
+    risk: u64,
+    max_slippage_bps: u16,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    if legs.len() != leg_accounts.len() || legs.len() > u8::MAX as usize {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = ExchangeInstruction::InitParlay {
+        risk,
+        max_slippage_bps,
+        legs: legs.to_vec(),
+        bump_seed,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(11 + 3 * leg_accounts.len());
+    accounts.push(AccountMeta::new(*user_main_account, true));
+    accounts.push(AccountMeta::new(*parlay_account, false));
+    accounts.push(AccountMeta::new(*bet_pool_state_account, false));
+    accounts.push(AccountMeta::new(*hp_usdt_account, false));
+    accounts.push(AccountMeta::new(*bet_usdt_account, false));
+    accounts.push(AccountMeta::new(*user_usdt_account, false));
+    accounts.push(AccountMeta::new_readonly(token_program_id::ID, false));
+    accounts.push(AccountMeta::new(*house_pda_account, false));
+    accounts.push(AccountMeta::new_readonly(*bet_pda_account, false));
+    accounts.push(AccountMeta::new(*house_pool_state_account, false));
+    accounts.push(AccountMeta::new_readonly(*divvy_house_program, false));
+    for (leg, leg_accounts) in legs.iter().zip(leg_accounts.iter()) {
+        accounts.push(AccountMeta::new(leg_accounts.market_state_account, false));
+        accounts.push(AccountMeta::new_readonly(leg_accounts.odds_feed_account, false));
+        match (leg.bet_type, leg_accounts.points_feed_account) {
+            (BetType::MoneyLine, None) => {}
+            (BetType::MoneyLine, Some(_)) | (_, None) => {
+                return Err(ProgramError::InvalidArgument);
+            }
+            (_, Some(points_feed_account)) => {
+                accounts.push(AccountMeta::new_readonly(points_feed_account, false));
+            }
+        }
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SettleParlay` instruction. `leg_market_accounts` are the
+/// markets of the parlay's legs, in leg order. Teasers also pass their
+/// teaser config.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_parlay(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    parlay_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    user_main_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    insurance_fund_usdt_account: &Pubkey,
+    divvy_foundation_proceeds_usdt_account: &Pubkey,
+    teaser_config_account: Option<&Pubkey>,
+    leg_market_accounts: &[Pubkey],
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::SettleParlay { bump_seed }.pack();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*initializer, true),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*parlay_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, false),
+        AccountMeta::new(*bet_usdt_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*user_main_account, false),
+        AccountMeta::new(*bet_pool_state_account, false),
+        AccountMeta::new(*hp_usdt_account, false),
+        AccountMeta::new(*insurance_fund_usdt_account, false),
+        AccountMeta::new(*divvy_foundation_proceeds_usdt_account, false),
+    ];
+    if let Some(teaser_config_account) = teaser_config_account {
+        accounts.push(AccountMeta::new_readonly(*teaser_config_account, false));
+    }
+    for leg_market_account in leg_market_accounts.iter() {
+        accounts.push(AccountMeta::new(*leg_market_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitTeaserConfig` instruction.
+pub fn init_teaser_config(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    teaser_config_account: &Pubkey,
+    teaser_points: i32,
+    odds: [i64; TeaserConfig::ODDS_LEN],
+) -> Result<Instruction, ProgramError> {
+    let data = ExchangeInstruction::InitTeaserConfig { teaser_points, odds }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*teaser_config_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitTeaser` instruction. `legs` and `leg_accounts` are in the
+/// same order, one entry per leg.
+#[allow(clippy::too_many_arguments)]
+pub fn init_teaser(
+    program_id: &Pubkey,
+    user_main_account: &Pubkey,
+    parlay_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    hp_usdt_account: &Pubkey,
+    bet_usdt_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    house_pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    house_pool_state_account: &Pubkey,
+    divvy_house_program: &Pubkey,
+    teaser_config_account: &Pubkey,
+    leg_accounts: &[TeaserLegAccounts],
+    legs: &[TeaserLegQuote],
+    risk: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    if legs.len() != leg_accounts.len() || legs.len() > u8::MAX as usize {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let data = ExchangeInstruction::InitTeaser {
+        risk,
+        legs: legs.to_vec(),
+        bump_seed,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(12 + 2 * leg_accounts.len());
+    accounts.push(AccountMeta::new(*user_main_account, true));
+    accounts.push(AccountMeta::new(*parlay_account, false));
+    accounts.push(AccountMeta::new(*bet_pool_state_account, false));
+    accounts.push(AccountMeta::new(*hp_usdt_account, false));
+    accounts.push(AccountMeta::new(*bet_usdt_account, false));
+    accounts.push(AccountMeta::new(*user_usdt_account, false));
+    accounts.push(AccountMeta::new_readonly(token_program_id::ID, false));
+    accounts.push(AccountMeta::new(*house_pda_account, false));
+    accounts.push(AccountMeta::new_readonly(*bet_pda_account, false));
+    accounts.push(AccountMeta::new(*house_pool_state_account, false));
+    accounts.push(AccountMeta::new_readonly(*divvy_house_program, false));
+    accounts.push(AccountMeta::new_readonly(*teaser_config_account, false));
+    for leg_accounts in leg_accounts.iter() {
+        accounts.push(AccountMeta::new(leg_accounts.market_state_account, false));
+        accounts.push(AccountMeta::new_readonly(leg_accounts.points_feed_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+fn result_oracle_accounts(oracles: &[ResultOracle]) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(3 * oracles.len());
+    for oracle in oracles.iter() {
+        accounts.push(AccountMeta::new_readonly(oracle.result_feed, false));
+        accounts.push(AccountMeta::new_readonly(oracle.team_a_score_feed, false));
+        accounts.push(AccountMeta::new_readonly(oracle.team_b_score_feed, false));
+    }
+    accounts
 }
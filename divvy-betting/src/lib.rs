@@ -1,17 +1,69 @@
 use error::ExchangeError;
-use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+use schema::authority;
+use solana_program::{msg, program_error::ProgramError, pubkey::{Pubkey, PubkeyError, MAX_SEED_LEN}};
+use std::convert::TryFrom;
 use spl_token::state::Account as TokenAccount;
-use state::{Bet, BetType, BettingPoolState, Market};
+use state::{BettingPoolState, Market, MarketSide};
 
-pub mod error;
 pub mod instruction;
 pub mod processor;
-pub mod schema;
+pub mod settlement;
 pub mod state;
 
+pub use divvycommon::{error, schema};
+
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 
+/// Number of slots after `SettleMarket` during which the authority can
+/// override the result before bets are paid out. Roughly one hour.
+pub const DISPUTE_WINDOW_SLOTS: u64 = 9_000;
+
+/// Default time after commencement before a market's settler may post the
+/// result by hand. One day.
+pub const DEFAULT_MANUAL_SETTLEMENT_GRACE_PERIOD: i64 = 86_400;
+
+/// Spread and total lines are stored in hundredths of a point, so -3.5 is
+/// `-350` and a quarter line of 2.25 is `225`.
+pub const POINTS_SCALE: i32 = 100;
+
+/// Decimal odds of parlay legs are stored scaled by this, so 1.909091 is `1_909_091`.
+pub const PARLAY_ODDS_SCALE: u64 = 1_000_000;
+
+/// Converts a line reported by a points feed to hundredths of a point.
+/// `None` if the value is not finite or does not fit.
+pub fn points_from_feed(value: f64) -> Option<i32> {
+    let points = (value * f64::from(POINTS_SCALE)).round();
+    if points.is_finite() && points >= f64::from(i32::MIN) && points <= f64::from(i32::MAX) {
+        Some(points as i32)
+    } else {
+        None
+    }
+}
+
+/// Seed of the account tracking a user's exposure on `market`.
+pub fn user_exposure_seed(market: &Pubkey) -> String {
+    let mut seed = market.to_string();
+    seed.truncate(MAX_SEED_LEN);
+    seed
+}
+
+/// Address of the account tracking `user`'s exposure on `market`. The user
+/// creates it with `create_account_with_seed`, so there is exactly one per market.
+pub fn user_exposure_address(
+    user: &Pubkey,
+    market: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_with_seed(user, &user_exposure_seed(market), program_id)
+}
+
+/// Address of the account tracking the liquidity locked by every market of
+/// the event `event_id`. The authority creates it with `create_account_with_seed`.
+pub fn event_group_address(event_id: &str, program_id: &Pubkey) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_with_seed(&authority::ID, event_id, program_id)
+}
+
 fn calculate_available_liquidity(
     hp_usdt_state: &TokenAccount,
     pool_state: &BettingPoolState,
@@ -21,6 +73,20 @@ fn calculate_available_liquidity(
     return Ok(available_liquidity);
 }
 
+/// Most a market or an event may lock: `max_share_bps` of the house liquidity,
+/// counting what is still available and what markets already locked or have live.
+fn calculate_liquidity_ceiling(
+    available_liquidity: u64,
+    pool_state: &BettingPoolState,
+    max_share_bps: u16,
+) -> Result<u64, ExchangeError> {
+    let house_liquidity = u128::from(available_liquidity)
+        + u128::from(pool_state.locked_liquidity)
+        + u128::from(pool_state.live_liquidity);
+    let ceiling = house_liquidity * u128::from(max_share_bps.min(10_000)) / 10_000;
+    u64::try_from(ceiling).map_err(|_| ExchangeError::AmountOverflow)
+}
+
 fn calculate_payout(odds: f64, risk: u64) -> Option<u64> {
     if odds >= 0.0 {
         Some((risk as f64 * (odds / 100f64)) as u64)
@@ -31,61 +97,44 @@ fn calculate_payout(odds: f64, risk: u64) -> Option<u64> {
     }
 }
 
+/// Decimal odds, scaled by `PARLAY_ODDS_SCALE`, for the American `odds`.
+fn calculate_decimal_odds(odds: f64) -> Option<u64> {
+    let payout = calculate_payout(odds, PARLAY_ODDS_SCALE)?;
+    PARLAY_ODDS_SCALE.checked_add(payout)
+}
+
+/// Slippage is measured on the payout, so a move in the bettor's favour is always accepted.
+fn is_within_slippage(quoted_payout: u64, feed_payout: u64, max_slippage_bps: u16) -> bool {
+    let max_slippage_bps = u128::from(max_slippage_bps.min(10_000));
+    let min_payout = u128::from(quoted_payout) * (10_000 - max_slippage_bps) / 10_000;
+    u128::from(feed_payout) >= min_payout
+}
+
 fn calculate_bust_payout(risk: u16, multiplier: u32) -> Option<u64> {
     Some((risk as f64 * (multiplier as f64 / 100f64)) as u64)
 }
 
 fn calculate_locked_liquidity(market_state: &Market) -> Result<u64, ExchangeError> {
-    //Calculating max loss
-    let mut locked_side_0 = 0u64;
-    let mut locked_side_1 = 0u64;
-    let mut locked_side_2 = 0u64;
-
-    if market_state.market_sides[0].payout
-        > market_state.market_sides[1]
-            .risk
-            .checked_add(market_state.market_sides[2].risk)
-            .ok_or(ExchangeError::AmountOverflow)?
-    {
-        locked_side_0 = market_state.market_sides[0]
-            .payout
-            .checked_sub(market_state.market_sides[1].risk)
-            .ok_or(ExchangeError::AmountOverflow)?
-            .checked_sub(market_state.market_sides[2].risk)
-            .ok_or(ExchangeError::AmountOverflow)?;
-    };
-    if market_state.market_sides[1].payout
-        > market_state.market_sides[0]
-            .risk
-            .checked_add(market_state.market_sides[2].risk)
-            .ok_or(ExchangeError::AmountOverflow)?
-    {
-        locked_side_1 = market_state.market_sides[1]
-            .payout
-            .checked_sub(market_state.market_sides[0].risk)
-            .ok_or(ExchangeError::AmountOverflow)?
-            .checked_sub(market_state.market_sides[2].risk)
-            .ok_or(ExchangeError::AmountOverflow)?;
-    };
-
-    if market_state.market_sides[2].payout
-        > market_state.market_sides[0]
-            .risk
-            .checked_add(market_state.market_sides[1].risk)
-            .ok_or(ExchangeError::AmountOverflow)?
-    {
-        locked_side_2 = market_state.market_sides[2]
-            .payout
-            .checked_sub(market_state.market_sides[0].risk)
-            .ok_or(ExchangeError::AmountOverflow)?
-            .checked_sub(market_state.market_sides[1].risk)
-            .ok_or(ExchangeError::AmountOverflow)?;
-    };
+    calculate_sides_locked_liquidity(market_state.active_sides())
+}
 
-    let locked_liquidity = *[locked_side_0, locked_side_1, locked_side_2]
+/// Max loss of the house over every outcome: the payout owed to the winning
+/// side less the risk collected from all the other sides.
+fn calculate_sides_locked_liquidity(market_sides: &[MarketSide]) -> Result<u64, ExchangeError> {
+    let total_risk = market_sides
         .iter()
-        .max()
-        .ok_or(ExchangeError::InvalidInstruction)?;
+        .try_fold(0u64, |total, market_side| total.checked_add(market_side.risk))
+        .ok_or(ExchangeError::AmountOverflow)?;
+
+    let mut locked_liquidity = 0u64;
+    for market_side in market_sides.iter() {
+        let other_sides_risk = total_risk
+            .checked_sub(market_side.risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        if market_side.payout > other_sides_risk {
+            locked_liquidity = locked_liquidity.max(market_side.payout - other_sides_risk);
+        }
+    }
 
     return Ok(locked_liquidity);
 }
@@ -113,52 +162,3 @@ fn pack_pubkey_option(value: &Option<Pubkey>, dst: &mut [u8; 33]) {
     }
 }
 
-fn get_bet_outcome(bet_state: &Bet, market_state: &Market) -> u8 {
-    if bet_state.bet_type == BetType::MoneyLine {
-        // Moneyline
-        if bet_state.user_market_side != market_state.result.pack() {
-            return 2;
-        } else {
-            return 1;
-        }
-    } else if bet_state.bet_type == BetType::Spread {
-        // Spread
-        if bet_state.user_market_side == 1u8 {
-            // Underdog (+ve)
-            if bet_state.points > market_state.team_a_score + market_state.team_b_score {
-                return 1;
-            } else {
-                return 2;
-            }
-        } else {
-            // Favorite
-            if market_state.team_a_score - bet_state.points > market_state.team_b_score {
-                return 1;
-            } else {
-                return 2;
-            }
-        }
-
-
-    } else if bet_state.bet_type == BetType::Total {
-        // Totals
-        if bet_state.user_market_side == 1u8 {
-            // Over
-            if bet_state.points > market_state.total_score {
-                return 1;
-            } else {
-                return 2;
-            }
-        } else {
-            // Under
-            if bet_state.points < market_state.total_score  {
-                return 1;
-            } else {
-                return 2;
-            }
-        }
-    } else {
-        // Push
-        return 3
-    }
-}
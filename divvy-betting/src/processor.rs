@@ -1,6 +1,6 @@
-use std::mem::size_of;
+use std::cmp::Ordering;
 
-use solana_program::{account_info::{next_account_info, AccountInfo}, entrypoint::ProgramResult, instruction::{AccountMeta, Instruction}, msg, program::{invoke, invoke_signed}, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar};
+use solana_program::{account_info::{next_account_info, AccountInfo}, clock::{Clock, UnixTimestamp}, entrypoint::ProgramResult, msg, program::{invoke, invoke_signed}, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar};
 
 use spl_token::{
     instruction::{transfer},
@@ -10,7 +10,7 @@ use spl_token::{
 //Switchboard dependencies
 use switchboard_program::{get_aggregator, get_aggregator_result, AggregatorState, RoundResult};
 
-use crate::{calculate_available_liquidity, calculate_bust_payout, calculate_locked_liquidity, calculate_payout, error::ExchangeError, get_bet_outcome, instruction::ExchangeInstruction, schema::{authority, divvy_house_program_id, token_program_id}, state::{Bet, BetType, BettingPoolState, BustBet, Market, MarketOutcome, MarketSide, Multiplier, SolBust}};
+use crate::{calculate_available_liquidity, calculate_bust_payout, calculate_liquidity_ceiling, calculate_locked_liquidity, calculate_payout, calculate_sides_locked_liquidity, error::ExchangeError, calculate_decimal_odds, is_within_slippage, instruction::{transfer_locked_liquidity, ExchangeInstruction, ParlayLegQuote, TeaserLegQuote}, points_from_feed, DEFAULT_MANUAL_SETTLEMENT_GRACE_PERIOD, DISPUTE_WINDOW_SLOTS, event_group_address, user_exposure_address, schema::{authority, divvy_house_program_id, token_program_id}, settlement::{bet_outcome, cash_out_value, is_valid_line, parlay_bettor_amount, settle, teased_line, teaser_bettor_amount, BetOutcome, MarketResult}, state::{unpack_str, AccountType, Bet, BetType, BettingPoolState, BustBet, EventGroup, FeedChecks, FuturesMarket, FuturesOutcome, Market, MarketMetadata, MarketOutcome, MarketSide, Parlay, ParlayLeg, ResultOracle, StakeLimits, TeaserConfig, UserExposure, Multiplier, SolBust}};
 
 use fixed::types::U64F64;
 
@@ -30,10 +30,11 @@ impl Processor {
                 points,
                 market_side,
                 bet_type, 
+                max_slippage_bps,
                 bump_seed
             } => {
                 msg!("Divvy - Init Bet");
-                Self::process_init_bet(accounts, risk, odds, points, market_side, bet_type, bump_seed, program_id)
+                Self::process_init_bet(accounts, risk, odds, points, market_side, bet_type, max_slippage_bps, bump_seed, program_id)
             }
             ExchangeInstruction::SettleBet { bump_seed } => {
                 msg!("Divvy - Settle Bet");
@@ -43,13 +44,13 @@ impl Processor {
                 msg!("Divvy - Settle Profit Loss");
                 Self::process_settle_pnl(accounts, bump_seed, program_id)
             }
-            ExchangeInstruction::InitMarket { bump_seed } => {
+            ExchangeInstruction::InitMarket { start_timestamp, side_count, metadata, bump_seed } => {
                 msg!("Divvy - Init Market");
-                Self::process_init_market(accounts, bump_seed, program_id)
+                Self::process_init_market(accounts, start_timestamp, side_count, metadata, bump_seed, program_id)
             }
-            ExchangeInstruction::InitFuturesMarket { bump_seed } => {
+            ExchangeInstruction::InitFuturesMarket { start_timestamp, side_count, bump_seed } => {
                 msg!("Divvy - Init Futures Market");
-                Self::process_init_futures_market(accounts, bump_seed, program_id)
+                Self::process_init_futures_market(accounts, start_timestamp, side_count, bump_seed, program_id)
             }
             ExchangeInstruction::SettleMarket { bump_seed } => {
                 msg!("Divvy - Settle Moneyline Market");
@@ -81,16 +82,183 @@ impl Processor {
                 msg!("Divvy - Settle Bust Bet");
                 Self::process_settle_bust_bet(accounts, program_id)
             }
+            ExchangeInstruction::VoidMarket { bump_seed } => {
+                msg!("Divvy - Void Market");
+                Self::process_void_market(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::RefundBet { bump_seed } => {
+                msg!("Divvy - Refund Bet");
+                Self::process_refund_bet(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::AddFuturesSides { bump_seed } => {
+                msg!("Divvy - Add Futures Sides");
+                Self::process_add_futures_sides(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::InitFuturesBet {
+                risk,
+                odds,
+                market_side,
+                max_slippage_bps,
+                bump_seed,
+            } => {
+                msg!("Divvy - Init Futures Bet");
+                Self::process_init_futures_bet(accounts, risk, odds, market_side, max_slippage_bps, bump_seed, program_id)
+            }
+            ExchangeInstruction::CommenceFuturesMarket { bump_seed } => {
+                msg!("Divvy - Commence Futures Market");
+                Self::process_commence_futures_market(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::SettleFuturesMarket { bump_seed } => {
+                msg!("Divvy - Settle Futures Market");
+                Self::process_settle_futures_market(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::SettleFuturesBet { bump_seed } => {
+                msg!("Divvy - Settle Futures Bet");
+                Self::process_settle_futures_bet(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::SettleFuturesPNL { bump_seed } => {
+                msg!("Divvy - Settle Futures Profit Loss");
+                Self::process_settle_futures_pnl(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::CloseMarket { bump_seed } => {
+                msg!("Divvy - Close Market");
+                Self::process_close_market(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::OverrideResult {
+                winning_side,
+                team_a_score,
+                team_b_score,
+                bump_seed,
+            } => {
+                msg!("Divvy - Override Result");
+                Self::process_override_result(accounts, winning_side, team_a_score, team_b_score, bump_seed, program_id)
+            }
+            ExchangeInstruction::ConfigureResultOracles { result_quorum, bump_seed } => {
+                msg!("Divvy - Configure Result Oracles");
+                Self::process_configure_result_oracles(accounts, result_quorum, bump_seed, program_id)
+            }
+            ExchangeInstruction::ConfigureFeedChecks { feed_checks, bump_seed } => {
+                msg!("Divvy - Configure Feed Checks");
+                Self::process_configure_feed_checks(accounts, feed_checks, bump_seed, program_id)
+            }
+            ExchangeInstruction::ConfigureManualSettlement { grace_period, bump_seed } => {
+                msg!("Divvy - Configure Manual Settlement");
+                Self::process_configure_manual_settlement(accounts, grace_period, bump_seed, program_id)
+            }
+            ExchangeInstruction::ManualSettleMarket {
+                winning_side,
+                team_a_score,
+                team_b_score,
+                bump_seed,
+            } => {
+                msg!("Divvy - Manual Settle Market");
+                Self::process_manual_settle_market(accounts, winning_side, team_a_score, team_b_score, bump_seed, program_id)
+            }
+            ExchangeInstruction::ConfigureStakeLimits { stake_limits, bump_seed } => {
+                msg!("Divvy - Configure Stake Limits");
+                Self::process_configure_stake_limits(accounts, stake_limits, bump_seed, program_id)
+            }
+            ExchangeInstruction::ConfigureLiquidityCeilings {
+                max_market_liquidity_bps,
+                max_event_liquidity_bps,
+            } => {
+                msg!("Divvy - Configure Liquidity Ceilings");
+                Self::process_configure_liquidity_ceilings(
+                    accounts,
+                    max_market_liquidity_bps,
+                    max_event_liquidity_bps,
+                )
+            }
+            ExchangeInstruction::SuspendMarket { bump_seed } => {
+                msg!("Divvy - Suspend Market");
+                Self::process_suspend_market(accounts, true, bump_seed, program_id)
+            }
+            ExchangeInstruction::ResumeMarket { bump_seed } => {
+                msg!("Divvy - Resume Market");
+                Self::process_suspend_market(accounts, false, bump_seed, program_id)
+            }
+            ExchangeInstruction::ConfigureLiveBetting {
+                live_betting,
+                acceptance_delay_slots,
+                bump_seed,
+            } => {
+                msg!("Divvy - Configure Live Betting");
+                Self::process_configure_live_betting(
+                    accounts,
+                    live_betting,
+                    acceptance_delay_slots,
+                    bump_seed,
+                    program_id,
+                )
+            }
+            ExchangeInstruction::AcceptLiveBet { bump_seed } => {
+                msg!("Divvy - Accept Live Bet");
+                Self::process_accept_live_bet(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::InitParlay {
+                risk,
+                max_slippage_bps,
+                legs,
+                bump_seed,
+            } => {
+                msg!("Divvy - Init Parlay");
+                Self::process_init_parlay(accounts, risk, max_slippage_bps, &legs, bump_seed, program_id)
+            }
+            ExchangeInstruction::SettleParlay { bump_seed } => {
+                msg!("Divvy - Settle Parlay");
+                Self::process_settle_parlay(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::InitTeaserConfig { teaser_points, odds } => {
+                msg!("Divvy - Init Teaser Config");
+                Self::process_init_teaser_config(accounts, teaser_points, &odds, program_id)
+            }
+            ExchangeInstruction::InitTeaser {
+                risk,
+                legs,
+                bump_seed,
+            } => {
+                msg!("Divvy - Init Teaser");
+                Self::process_init_teaser(accounts, risk, &legs, bump_seed, program_id)
+            }
+            ExchangeInstruction::ConfigureCashOut {
+                cash_out,
+                cash_out_margin_bps,
+                bump_seed,
+            } => {
+                msg!("Divvy - Configure Cash Out");
+                Self::process_configure_cash_out(accounts, cash_out, cash_out_margin_bps, bump_seed, program_id)
+            }
+            ExchangeInstruction::CashOut { min_value, bump_seed } => {
+                msg!("Divvy - Cash Out");
+                Self::process_cash_out(accounts, min_value, bump_seed, program_id)
+            }
+            ExchangeInstruction::VoidFuturesMarket { bump_seed } => {
+                msg!("Divvy - Void Futures Market");
+                Self::process_void_futures_market(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::RefundFuturesBet { bump_seed } => {
+                msg!("Divvy - Refund Futures Bet");
+                Self::process_refund_futures_bet(accounts, bump_seed, program_id)
+            }
+            ExchangeInstruction::MigrateBettingPool => {
+                msg!("Divvy - Migrate Betting Pool");
+                Self::process_migrate_betting_pool(accounts, program_id)
+            }
+            ExchangeInstruction::MigrateMarket => {
+                msg!("Divvy - Migrate Market");
+                Self::process_migrate_market(accounts, program_id)
+            }
         }
     }
 
     fn process_init_bet(
         accounts: &[AccountInfo],
         risk: u64,
-        _odds: u64,
-        points: u16,
+        odds: i64,
+        points: i32,
         market_side: u8,
         bet_type: BetType,
+        max_slippage_bps: u16,
         bump_seed: u8,
         program_id: &Pubkey,
     ) -> ProgramResult {
@@ -116,6 +284,14 @@ impl Processor {
         let bet_pda_account = next_account_info(accounts_iter)?;
         let pool_state_account = next_account_info(accounts_iter)?;
         let divvy_hp_program = next_account_info(accounts_iter)?;
+        let user_exposure_account = next_account_info(accounts_iter)?;
+        let event_group_account = next_account_info(accounts_iter)?;
+        // Moneyline bets have no line to check
+        let points_feed_account = if bet_type == BetType::MoneyLine {
+            None
+        } else {
+            Some(next_account_info(accounts_iter)?)
+        };
         msg!("Validating accounts");
         //Checking if market is initialized
         msg!("Checking market initialization");
@@ -161,19 +337,53 @@ impl Processor {
             return Err(ExchangeError::BettingFrozen.into());
         }
 
+        // Live markets keep taking bets after commencement, priced from their live odds feeds
+        let live = market_state.live_betting && market_state.result == MarketOutcome::Commenced;
+
         msg!("Checking if market is not commenced or settled yet");
-        if market_state.result != MarketOutcome::NotYetCommenced {
+        if market_state.result != MarketOutcome::NotYetCommenced && !live {
             return Err(ExchangeError::MarketCommenced.into());
         }
-        //TODO fix this
-        //Checking if feed account is right
-        // if market_state.market_sides[market_side as usize]
-        //     .odds_feed_account
-        //     .ok_or(ExchangeError::InvalidInstruction)?
-        //     != *feed_account.key
-        // {
-        //     return Err(ExchangeError::InvalidFeedAccount.into());
-        // }
+
+        msg!("Checking if market start time has passed");
+        if !live && Clock::get()?.unix_timestamp >= market_state.start_timestamp {
+            return Err(ExchangeError::MarketStarted.into());
+        }
+
+        msg!("Checking if market is suspended");
+        if market_state.suspended {
+            return Err(ExchangeError::MarketSuspended.into());
+        }
+
+        msg!("Checking market side");
+        if market_side >= market_state.side_count {
+            return Err(ExchangeError::InvalidMarketSide.into());
+        }
+        if live {
+            msg!("Checking live odds feed account");
+            if market_state.live_odds_feed_accounts[market_side as usize] != Some(*feed_account.key) {
+                return Err(ExchangeError::InvalidFeedAccount.into());
+            }
+        } else {
+            msg!("Checking market side feed account");
+            if market_state.market_sides[market_side as usize]
+                .odds_feed_account
+                .ok_or(ExchangeError::InvalidFeedAccount)?
+                != *feed_account.key
+            {
+                return Err(ExchangeError::InvalidFeedAccount.into());
+            }
+        }
+
+        msg!("Checking bet line");
+        if !is_valid_line(bet_type, points) {
+            return Err(ExchangeError::InvalidBetLine.into());
+        }
+        if let Some(points_feed_account) = points_feed_account {
+            if !Self::line_matches_feed(points_feed_account, &market_state, market_side, points)? {
+                return Err(ExchangeError::LineMismatch.into());
+            }
+        }
 
         msg!("Checking if risk is non zero");
         if risk == 0 {
@@ -184,11 +394,7 @@ impl Processor {
 
 
         msg!("Getting odds from the Switchboard");
-        let aggregator: AggregatorState = get_aggregator(feed_account)?;
-        let round_result: RoundResult = get_aggregator_result(&aggregator)?;
-        let feed_odds = round_result
-            .result
-            .ok_or(ExchangeError::FeedNotInitialized)?;
+        let feed_odds = Self::read_feed(feed_account, &market_state.feed_checks)?;
         if feed_odds >= 0f64 {
             msg!("- Odds from feed: Positive:");
             msg!(0, 0, 0, 0, feed_odds as u64);
@@ -197,20 +403,34 @@ impl Processor {
             msg!(0, 0, 0, 0, -feed_odds as u64);
         }
 
-        //TODO comparison of provided odds & feed odds.
-
         //Calculate payout
         let payout = calculate_payout(feed_odds, risk).ok_or(ExchangeError::InvalidInstruction)?;
         msg!("- Bet payout");
         msg!(0, 0, 0, 0, payout);
 
-        if payout > available_liquidty {
-            return Err(ExchangeError::NotEnoughAvailableLiquidityForBet.into());
+        msg!("Checking feed odds against quoted odds");
+        let quoted_payout = calculate_payout(odds as f64, risk).ok_or(ExchangeError::InvalidInstruction)?;
+        msg!("- Quoted payout");
+        msg!(0, 0, 0, 0, quoted_payout);
+        if !is_within_slippage(quoted_payout, payout, max_slippage_bps) {
+            return Err(ExchangeError::OddsSlippageExceeded.into());
         }
+
         // Payout coming out as zero, throw error
         if payout == 0u64  {
             return Err(ExchangeError::PayoutZero.into());
         }
+        // Live bets count against the limits from placement, so a stake the
+        // market would never book is refused before the risk is taken.
+        Self::add_user_exposure(
+            user_exposure_account,
+            initializer.key,
+            market_state_account.key,
+            &market_state.stake_limits,
+            risk,
+            payout,
+            program_id,
+        )?;
         // Increment pending bets
         msg!("Incrementing market pending bets.");
         market_state.pending_bets = market_state
@@ -224,50 +444,6 @@ impl Processor {
             .checked_add(1)
             .ok_or(ExchangeError::AmountOverflow)?;
 
-        //Calculating locked liquidity
-        let new_locked_liquidity;
-        if bet_type == BetType::MoneyLine {
-            //Add risk & payout in market side
-            let current_market_side_risk = market_state.market_sides[market_side as usize].risk;
-            let current_market_side_payout = market_state.market_sides[market_side as usize].payout;
-            let old_locked_liquidity = calculate_locked_liquidity(&market_state)?;
-            market_state.market_sides[market_side as usize].risk = current_market_side_risk
-            .checked_add(risk)
-            .ok_or(ExchangeError::AmountOverflow)?;
-            market_state.market_sides[market_side as usize].payout = current_market_side_payout
-                .checked_add(payout)
-                .ok_or(ExchangeError::AmountOverflow)?;
-            let new_moneyline_locked_liquidity = calculate_locked_liquidity(&market_state)?;
-            new_locked_liquidity = market_state.locked_liquidity
-                                    .checked_add(new_moneyline_locked_liquidity)
-                                    .ok_or(ExchangeError::AmountOverflow)?
-                                    .checked_sub(old_locked_liquidity)
-                                    .ok_or(ExchangeError::AmountOverflow)?;
-        } else {
-            // TODO Use checked math here
-             new_locked_liquidity = market_state.locked_liquidity
-             .checked_add(payout)
-             .ok_or(ExchangeError::AmountOverflow)?;
-        }
-        let current_locked_liquidity = market_state.locked_liquidity;
-        let current_pool_locked_liquidity = pool_state.locked_liquidity;
-
-        market_state.locked_liquidity = new_locked_liquidity;
-        pool_state.locked_liquidity = current_pool_locked_liquidity
-            .checked_sub(current_locked_liquidity)
-            .ok_or(ExchangeError::AmountOverflow)?
-            .checked_add(new_locked_liquidity)
-            .ok_or(ExchangeError::NotEnoughAvailableLiquidityForBet)?;
-
-        msg!("- Market locked liquidity from");
-        msg!(0, 0, 0, 0, current_locked_liquidity);
-        msg!("- Market locked liquidity to");
-        msg!(0, 0, 0, 0, new_locked_liquidity);
-        msg!("- Pool locked liquidity from");
-        msg!(0, 0, 0, 0, current_pool_locked_liquidity);
-        msg!("- Pool locked liquidity to");
-        msg!(0, 0, 0, 0, pool_state.locked_liquidity);
-
         //Transfer USDT from user account to bet pool account
         let transfer_instruction = transfer(
             &token_program.key,
@@ -287,71 +463,42 @@ impl Processor {
                 token_program.clone(),
             ],
         )?;
-        if new_locked_liquidity > current_locked_liquidity {
-            let usdt_amount = new_locked_liquidity
-                                    .checked_sub(current_locked_liquidity)
-                                    .ok_or(ExchangeError::AmountOverflow)?;
-            let signer_pubkeys = &[bet_pda_account.key];
-    
-            let mut data = Vec::with_capacity(size_of::<Self>());
-            data.push(4);
-            data.extend_from_slice(&usdt_amount.to_le_bytes());
-            data.extend_from_slice(&bump_seed.to_le_bytes());
-    
-            let mut accounts = Vec::with_capacity(5 + signer_pubkeys.len());
-            accounts.push(AccountMeta::new_readonly(*token_program.key, false));
-            accounts.push(AccountMeta::new(*pda_account.key, false));
-            for signer_pubkey in signer_pubkeys.iter() {
-                accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
-            }
-            accounts.push(AccountMeta::new(*bet_usdt_account.key, false));
-            accounts.push(AccountMeta::new(*hp_usdt_account.key, false));
-            accounts.push(AccountMeta::new(*pool_state_account.key, false));
-    
-            let instruction = Instruction {
-                program_id: *divvy_hp_program.key,
-                accounts,
-                data,
-            };
-            msg!("Transfer locked liquidity");
-            invoke_signed(
-                    &instruction,
-                    &[
-                        token_program.clone(),
-                        pda_account.clone(),
-                        bet_pda_account.clone(),
-                        bet_usdt_account.clone(),
-                        hp_usdt_account.clone(),
-                        pool_state_account.clone(),
-                    ],
-                    &[&[b"divvybetting", &[251]]],
-                )?;
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_add(risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        let accept_slot = if live {
+            // Live bets are only booked once their odds still hold after the
+            // acceptance delay, so a bettor watching the event cannot beat the feed.
+            msg!("Holding live bet until its acceptance delay passes");
+            Clock::get()?
+                .slot
+                .checked_add(market_state.live_acceptance_delay_slots)
+                .ok_or(ExchangeError::AmountOverflow)?
         } else {
-            let usdt_amount = current_locked_liquidity
-                                    .checked_sub(new_locked_liquidity)
-                                    .ok_or(ExchangeError::AmountOverflow)?;
-            let transfer_instruction = transfer(
-                &token_program.key,
-                &bet_usdt_account.key,
-                &hp_usdt_account.key,
-                &bet_pda_account.key,
-                &[&bet_pda_account.key],
-                usdt_amount
-            )?;
-            msg!("Transferring extra locked liquidity back to house pool");
-            invoke_signed(
-                &transfer_instruction,
-                &[
-                    hp_usdt_account.clone(),
-                    bet_usdt_account.clone(),
-                    bet_pda_account.clone(),
-                    token_program.clone(),
-                ],
-                //To Do Please test bump seed thing
-                &[&[b"divvybetting", &[251]]],
+            Self::book_bet(
+                &mut market_state,
+                &mut pool_state,
+                risk,
+                payout,
+                market_side,
+                bet_type,
+                available_liquidty,
+                bump_seed,
+                token_program,
+                pda_account,
+                bet_pda_account,
+                bet_usdt_account,
+                hp_usdt_account,
+                pool_state_account,
+                divvy_hp_program,
+                event_group_account,
+                program_id,
             )?;
-        }
-       
+            0
+        };
+
         // Initialize bet state
         bet_state = Bet {
             is_initialized: true,
@@ -363,12 +510,11 @@ impl Processor {
             points: points,
             user_market_side: market_side,
             outcome: 0, //Outcome 0 as market not settled.
-            bet_type: bet_type
+            bet_type: bet_type,
+            pending_acceptance: live,
+            accept_slot,
         };
 
-        // Increment bettor balance
-        market_state.bettor_balance  = market_state.bettor_balance + risk + new_locked_liquidity - current_locked_liquidity;
-
         // Write the accounts
         Bet::pack(bet_state, &mut bet_account.data.borrow_mut())?;
         BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
@@ -377,226 +523,3487 @@ impl Processor {
         Ok(())
     }
 
-    fn process_settle_pnl(
-        accounts: &[AccountInfo],
+    /// Books a priced bet on a moneyline market: checks the liquidity
+    /// ceilings, adds the bet to its market side and moves the extra liquidity
+    /// the market needs out of the house pool. On a commenced market that
+    /// liquidity is live straight away. The caller has already added the bet
+    /// to the user's exposure.
+    #[allow(clippy::too_many_arguments)]
+    fn book_bet<'a>(
+        market_state: &mut Market,
+        pool_state: &mut BettingPoolState,
+        risk: u64,
+        payout: u64,
+        market_side: u8,
+        bet_type: BetType,
+        available_liquidity: u64,
         bump_seed: u8,
-        program_id: &Pubkey
+        token_program: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        bet_pda_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        hp_usdt_account: &AccountInfo<'a>,
+        pool_state_account: &AccountInfo<'a>,
+        divvy_hp_program: &AccountInfo<'a>,
+        event_group_account: &AccountInfo<'a>,
+        program_id: &Pubkey,
     ) -> ProgramResult {
-        let accounts_iter = &mut accounts.iter();
-        let _initializer = next_account_info(accounts_iter)?;
-        let token_program = next_account_info(accounts_iter)?;
-        let market_state_account = next_account_info(accounts_iter)?;
-        let pda_account = next_account_info(accounts_iter)?;
-        let bet_usdt_account = next_account_info(accounts_iter)?;
-        let bet_pool_state_account = next_account_info(accounts_iter)?;
-        let hp_usdt_account = next_account_info(accounts_iter)?;
-        let insurance_fund_usdt_account = next_account_info(accounts_iter)?;
-        let divvy_foundation_proceeds_usdt = next_account_info(accounts_iter)?;
-        // Unpack token accounts to verify their length
-        msg!("Check token account accounts length");
-        TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
-        TokenAccount::unpack(&bet_usdt_account.data.borrow())?;
-        TokenAccount::unpack(&insurance_fund_usdt_account.data.borrow())?;
-        TokenAccount::unpack(&divvy_foundation_proceeds_usdt.data.borrow())?;
-        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
-        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
-        let usd_state = TokenAccount::unpack(&bet_usdt_account.data.borrow())?;
-
-        if *insurance_fund_usdt_account.key != pool_state.insurance_fund_usdt {
-            return Err(ExchangeError::InvalidInsuranceFundUsdtAccount.into());
+        if payout > available_liquidity {
+            return Err(ExchangeError::NotEnoughAvailableLiquidityForBet.into());
         }
-        if *divvy_foundation_proceeds_usdt.key != pool_state.divvy_foundation_proceeds_usdt {
-            return Err(ExchangeError::InvalidDivvyFoundationUsdtAccount.into());
+
+        //Calculating locked liquidity
+        let new_locked_liquidity =
+            Self::booked_locked_liquidity(market_state, risk, payout, market_side, bet_type)?;
+        if bet_type == BetType::MoneyLine {
+            //Add risk & payout in market side
+            let booked_market_side = &mut market_state.market_sides[market_side as usize];
+            booked_market_side.risk = booked_market_side
+                .risk
+                .checked_add(risk)
+                .ok_or(ExchangeError::AmountOverflow)?;
+            booked_market_side.payout = booked_market_side
+                .payout
+                .checked_add(payout)
+                .ok_or(ExchangeError::AmountOverflow)?;
         }
-        if *token_program.key != token_program_id::ID {
-            return Err(ExchangeError::InvalidInstruction.into());
+
+        Self::lock_market_liquidity(
+            market_state,
+            pool_state,
+            new_locked_liquidity,
+            available_liquidity,
+            bump_seed,
+            token_program,
+            pda_account,
+            bet_pda_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            pool_state_account,
+            divvy_hp_program,
+            event_group_account,
+            program_id,
+        )
+    }
+
+    /// Locked liquidity of a market once a bet is added to it. Moneyline bets
+    /// can offset the other sides, every other bet locks its full payout.
+    fn booked_locked_liquidity(
+        market_state: &Market,
+        risk: u64,
+        payout: u64,
+        market_side: u8,
+        bet_type: BetType,
+    ) -> Result<u64, ProgramError> {
+        if bet_type != BetType::MoneyLine {
+            return Ok(market_state
+                .locked_liquidity
+                .checked_add(payout)
+                .ok_or(ExchangeError::AmountOverflow)?);
         }
-        if *market_state_account.owner != *program_id {
-            return Err(ExchangeError::InvalidMarketAccount.into());
+        let mut market_sides = market_state.market_sides;
+        let booked_market_side = &mut market_sides[market_side as usize];
+        booked_market_side.risk = booked_market_side
+            .risk
+            .checked_add(risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        booked_market_side.payout = booked_market_side
+            .payout
+            .checked_add(payout)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        let old_moneyline_locked_liquidity = calculate_locked_liquidity(market_state)?;
+        let new_moneyline_locked_liquidity =
+            calculate_sides_locked_liquidity(&market_sides[..market_state.side_count as usize])?;
+        Ok(market_state
+            .locked_liquidity
+            .checked_add(new_moneyline_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_sub(old_moneyline_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?)
+    }
+
+    /// Whether a bet fits the available liquidity and the market and event
+    /// liquidity ceilings. Nothing is booked or written.
+    #[allow(clippy::too_many_arguments)]
+    fn bet_fits_liquidity(
+        market_state: &Market,
+        pool_state: &BettingPoolState,
+        risk: u64,
+        payout: u64,
+        market_side: u8,
+        bet_type: BetType,
+        available_liquidity: u64,
+        event_group_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<bool, ProgramError> {
+        if payout > available_liquidity {
+            return Ok(false);
         }
-        // Checking bet pool usdt account
-        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
-            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        let new_locked_liquidity =
+            Self::booked_locked_liquidity(market_state, risk, payout, market_side, bet_type)?;
+        match Self::check_liquidity_ceilings(
+            market_state,
+            pool_state,
+            new_locked_liquidity,
+            available_liquidity,
+            event_group_account,
+            program_id,
+        ) {
+            Ok(_) => Ok(true),
+            Err(error)
+                if error == ExchangeError::MarketLiquidityCeilingExceeded.into()
+                    || error == ExchangeError::EventLiquidityCeilingExceeded.into() =>
+            {
+                Ok(false)
+            }
+            Err(error) => Err(error),
         }
+    }
 
-        if *market_state_account.owner != *program_id {
-            return Err(ExchangeError::InvalidMarketAccount.into());
+    /// Holds growth of a market's locked liquidity to the market and event
+    /// liquidity ceilings. Returns the market's event group with the new
+    /// liquidity, still to be written, or `None` if it belongs to no event.
+    fn check_liquidity_ceilings(
+        market_state: &Market,
+        pool_state: &BettingPoolState,
+        new_locked_liquidity: u64,
+        available_liquidity: u64,
+        event_group_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Option<EventGroup>, ProgramError> {
+        msg!("Checking market liquidity ceiling");
+        let market_liquidity_ceiling = calculate_liquidity_ceiling(
+            available_liquidity,
+            pool_state,
+            pool_state.max_market_liquidity_bps,
+        )?;
+        if new_locked_liquidity > market_state.locked_liquidity
+            && new_locked_liquidity > market_liquidity_ceiling
+        {
+            return Err(ExchangeError::MarketLiquidityCeilingExceeded.into());
         }
-        if *token_program.key != token_program_id::ID {
-            return Err(ExchangeError::InvalidInstruction.into());
+        msg!("Checking event liquidity ceiling");
+        let event_liquidity_ceiling = calculate_liquidity_ceiling(
+            available_liquidity,
+            pool_state,
+            pool_state.max_event_liquidity_bps,
+        )?;
+        Self::updated_event_group(
+            event_group_account,
+            &market_state.metadata,
+            market_state.locked_liquidity,
+            new_locked_liquidity,
+            event_liquidity_ceiling,
+            program_id,
+        )
+    }
+
+    /// Moves a market's locked liquidity to `new_locked_liquidity`. Growth is
+    /// held to the market and event liquidity ceilings, and the difference
+    /// moves between the house pool and the betting pool.
+    #[allow(clippy::too_many_arguments)]
+    fn lock_market_liquidity<'a>(
+        market_state: &mut Market,
+        pool_state: &mut BettingPoolState,
+        new_locked_liquidity: u64,
+        available_liquidity: u64,
+        bump_seed: u8,
+        token_program: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        bet_pda_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        hp_usdt_account: &AccountInfo<'a>,
+        pool_state_account: &AccountInfo<'a>,
+        divvy_hp_program: &AccountInfo<'a>,
+        event_group_account: &AccountInfo<'a>,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if let Some(event_group) = Self::check_liquidity_ceilings(
+            market_state,
+            pool_state,
+            new_locked_liquidity,
+            available_liquidity,
+            event_group_account,
+            program_id,
+        )? {
+            EventGroup::pack(event_group, &mut event_group_account.data.borrow_mut())?;
         }
 
-        //Checking if betting is frozen
-        if pool_state.frozen_betting {
-            return Err(ExchangeError::BettingFrozen.into());
+        let current_locked_liquidity = market_state.locked_liquidity;
+        market_state.locked_liquidity = new_locked_liquidity;
+        msg!("- Market locked liquidity from");
+        msg!(0, 0, 0, 0, current_locked_liquidity);
+        msg!("- Market locked liquidity to");
+        msg!(0, 0, 0, 0, new_locked_liquidity);
+
+        if market_state.result == MarketOutcome::Commenced {
+            let current_pool_live_liquidity = pool_state.live_liquidity;
+            pool_state.live_liquidity = current_pool_live_liquidity
+                .checked_sub(current_locked_liquidity)
+                .ok_or(ExchangeError::AmountOverflow)?
+                .checked_add(new_locked_liquidity)
+                .ok_or(ExchangeError::NotEnoughAvailableLiquidityForBet)?;
+            msg!("- Pool live liquidity from");
+            msg!(0, 0, 0, 0, current_pool_live_liquidity);
+            msg!("- Pool live liquidity to");
+            msg!(0, 0, 0, 0, pool_state.live_liquidity);
+        } else {
+            let current_pool_locked_liquidity = pool_state.locked_liquidity;
+            pool_state.locked_liquidity = current_pool_locked_liquidity
+                .checked_sub(current_locked_liquidity)
+                .ok_or(ExchangeError::AmountOverflow)?
+                .checked_add(new_locked_liquidity)
+                .ok_or(ExchangeError::NotEnoughAvailableLiquidityForBet)?;
+            msg!("- Pool locked liquidity from");
+            msg!(0, 0, 0, 0, current_pool_locked_liquidity);
+            msg!("- Pool locked liquidity to");
+            msg!(0, 0, 0, 0, pool_state.locked_liquidity);
         }
-        let result = match market_state.result {
-            MarketOutcome::MarketSide0Won => 0,
-            MarketOutcome::MarketSide1Won => 1,
-            MarketOutcome::MarketSide2Won => 2,
-            _ => return Err(ExchangeError::NotValidMarketResult.into()),
-        };
-        msg!("Market Bettor balance: {}", market_state.bettor_balance);
-        msg!("Market locked liquidity: {}",  market_state.locked_liquidity);
-        if market_state.pending_bets == 0 {
-            if market_state.locked_liquidity>=market_state.bettor_balance {
-                // House made a loss, return locked liquidity
-                msg!("Transfering locked liquidity to house pool");
-                let transfer_instruction = transfer(
-                    &token_program.key,
-                    &bet_usdt_account.key,
-                    &hp_usdt_account.key,
-                    &pda_account.key,
-                    &[&pda_account.key],
-                    (market_state.bettor_balance).clone(),
-                )?;
-                invoke_signed(
-                    &transfer_instruction,
-                    &[
-                        bet_usdt_account.clone(),
-                        hp_usdt_account.clone(),
-                        pda_account.clone(),
-                        token_program.clone(),
-                    ],
-                    &[&[b"divvybetting", &[251]]],
-                )?;
-            } else {
-                // House made a profit, send 5% to Profit pool and 1% to insurance pool
-                let house_profit_frac: U64F64 = U64F64::from_num(market_state.bettor_balance - market_state.locked_liquidity);
-                msg!("House profit: {}", house_profit_frac);
-                let insurance_fund_fee: u64 = (house_profit_frac * U64F64::from_num(0.01))
-                    .checked_to_num()
-                    .ok_or(ExchangeError::AmountOverflow)?;
-                msg!("Insurance fees: {}", insurance_fund_fee);
-                let divvy_foundation_fee: u64 = (house_profit_frac * U64F64::from_num(0.05))
-                    .checked_to_num()
-                    .ok_or(ExchangeError::AmountOverflow)?;
-                msg!("Foundation fee: {}", divvy_foundation_fee);
-                let total_house_profit: u64 = ((house_profit_frac * U64F64::from_num(0.94)) +  U64F64::from_num(market_state.locked_liquidity))
-                    .checked_to_num()
-                    .ok_or(ExchangeError::AmountOverflow)?;
-                msg!("Total House return: {}", total_house_profit);
-                msg!("Transfering USDT to the insurance fund");
-                let transfer_instruction = transfer(
-                    &token_program.key,
-                    &bet_usdt_account.key,
-                    &insurance_fund_usdt_account.key,
-                    &pda_account.key,
-                    &[&pda_account.key],
-                    insurance_fund_fee.clone(),
-                )?;
-                invoke_signed(
-                    &transfer_instruction,
-                    &[
-                        bet_usdt_account.clone(),
-                        insurance_fund_usdt_account.clone(),
-                        pda_account.clone(),
-                        token_program.clone(),
-                    ],
-                    &[&[b"divvybetting", &[251]]],
-                )?;
-                msg!("Transfering USDT to the Divvy foundation");
-                let transfer_instruction = transfer(
-                    &token_program.key,
-                    &bet_usdt_account.key,
-                    &divvy_foundation_proceeds_usdt.key,
-                    &pda_account.key,
-                    &[&pda_account.key],
-                    divvy_foundation_fee.clone(),
-                )?;
-                invoke_signed(
-                    &transfer_instruction,
+
+        Self::rebalance_locked_liquidity(
+            current_locked_liquidity,
+            new_locked_liquidity,
+            bump_seed,
+            token_program,
+            pda_account,
+            bet_pda_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            pool_state_account,
+            divvy_hp_program,
+        )?;
+
+        // The liquidity moved in from the house pool is held for the bettors
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_add(new_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_sub(current_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        Ok(())
+    }
+
+    /// Hands back the risk of a live bet that was never booked. The bet ends
+    /// with outcome 4, like a bet refunded on a voided market.
+    fn refund_unbooked_bet<'a>(
+        market_state: &mut Market,
+        pool_state: &mut BettingPoolState,
+        bet_state: &mut Bet,
+        token_program: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        user_usdt_account: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        msg!("Decrementing market pending bets.");
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("Decrementing betting pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_sub(bet_state.user_risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        let transfer_instruction = transfer(
+            &token_program.key,
+            &bet_usdt_account.key,
+            &user_usdt_account.key,
+            &pda_account.key,
+            &[&pda_account.key],
+            bet_state.user_risk,
+        )?;
+        msg!("Calling the token program to refund the risk to user.");
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                user_usdt_account.clone(),
+                bet_usdt_account.clone(),
+                pda_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[b"divvybetting", &[251]]],
+        )?;
+        bet_state.pending_acceptance = false;
+        bet_state.outcome = BetOutcome::Void.pack();
+        Ok(())
+    }
+
+    /// Replaces a market's share of its event group's locked liquidity,
+    /// creating the group record on the event's first bet. Growth that takes
+    /// the group above `ceiling` is refused, releases always go through.
+    fn update_event_liquidity(
+        event_group_account: &AccountInfo,
+        metadata: &MarketMetadata,
+        current_locked_liquidity: u64,
+        new_locked_liquidity: u64,
+        ceiling: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if let Some(event_group) = Self::updated_event_group(
+            event_group_account,
+            metadata,
+            current_locked_liquidity,
+            new_locked_liquidity,
+            ceiling,
+            program_id,
+        )? {
+            EventGroup::pack(event_group, &mut event_group_account.data.borrow_mut())?;
+        }
+        Ok(())
+    }
+
+    /// The event group of `update_event_liquidity` without writing it, or
+    /// `None` for markets that belong to no event.
+    fn updated_event_group(
+        event_group_account: &AccountInfo,
+        metadata: &MarketMetadata,
+        current_locked_liquidity: u64,
+        new_locked_liquidity: u64,
+        ceiling: u64,
+        program_id: &Pubkey,
+    ) -> Result<Option<EventGroup>, ProgramError> {
+        // Legacy markets have no metadata and belong to no event
+        if metadata.event_id[0] == 0 {
+            return Ok(None);
+        }
+        msg!("Checking event group account");
+        let event_id =
+            unpack_str(&metadata.event_id).ok_or(ExchangeError::InvalidMarketMetadata)?;
+        let expected_event_group_account = event_group_address(event_id, program_id)
+            .map_err(|_| ExchangeError::InvalidEventGroupAccount)?;
+        if *event_group_account.key != expected_event_group_account
+            || *event_group_account.owner != *program_id
+        {
+            return Err(ExchangeError::InvalidEventGroupAccount.into());
+        }
+        let mut event_group = EventGroup::unpack_unchecked(&event_group_account.data.borrow())?;
+        if !event_group.is_initialized {
+            if !Rent::get()?.is_exempt(
+                **event_group_account.lamports.borrow(),
+                event_group_account.data_len(),
+            ) {
+                return Err(ProgramError::AccountNotRentExempt);
+            }
+            event_group = EventGroup {
+                is_initialized: true,
+                event_id: metadata.event_id,
+                locked_liquidity: 0,
+            };
+        }
+
+        event_group.locked_liquidity = event_group
+            .locked_liquidity
+            .checked_sub(current_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_add(new_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Event locked liquidity");
+        msg!(0, 0, 0, 0, event_group.locked_liquidity);
+        if new_locked_liquidity > current_locked_liquidity && event_group.locked_liquidity > ceiling {
+            return Err(ExchangeError::EventLiquidityCeilingExceeded.into());
+        }
+        Ok(Some(event_group))
+    }
+
+    /// Checks a new bet against the market's stake limits and adds it to the
+    /// user's exposure on the market, creating the exposure record on the first bet.
+    fn add_user_exposure(
+        user_exposure_account: &AccountInfo,
+        user: &Pubkey,
+        market: &Pubkey,
+        stake_limits: &StakeLimits,
+        risk: u64,
+        payout: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        msg!("Checking stake limits");
+        if risk < stake_limits.min_stake {
+            return Err(ExchangeError::StakeBelowMinimum.into());
+        }
+        if risk > stake_limits.max_stake {
+            return Err(ExchangeError::StakeAboveMaximum.into());
+        }
+
+        msg!("Checking user exposure account");
+        let expected_exposure_account = user_exposure_address(user, market, program_id)
+            .map_err(|_| ExchangeError::InvalidUserExposureAccount)?;
+        if *user_exposure_account.key != expected_exposure_account
+            || *user_exposure_account.owner != *program_id
+        {
+            return Err(ExchangeError::InvalidUserExposureAccount.into());
+        }
+        let mut exposure = UserExposure::unpack_unchecked(&user_exposure_account.data.borrow())?;
+        if !exposure.is_initialized {
+            if !Rent::get()?.is_exempt(
+                **user_exposure_account.lamports.borrow(),
+                user_exposure_account.data_len(),
+            ) {
+                return Err(ProgramError::AccountNotRentExempt);
+            }
+            exposure = UserExposure {
+                is_initialized: true,
+                market: *market,
+                user: *user,
+                risk: 0,
+                payout: 0,
+            };
+        }
+
+        exposure.risk = exposure
+            .risk
+            .checked_add(risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        exposure.payout = exposure
+            .payout
+            .checked_add(payout)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- User payout on market");
+        msg!(0, 0, 0, 0, exposure.payout);
+        if exposure.payout > stake_limits.max_payout_per_user {
+            return Err(ExchangeError::UserExposureExceeded.into());
+        }
+
+        UserExposure::pack(exposure, &mut user_exposure_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Moves the change in a market's locked liquidity between the house pool
+    /// and the betting pool.
+    #[allow(clippy::too_many_arguments)]
+    fn rebalance_locked_liquidity<'a>(
+        current_locked_liquidity: u64,
+        new_locked_liquidity: u64,
+        bump_seed: u8,
+        token_program: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        bet_pda_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        hp_usdt_account: &AccountInfo<'a>,
+        pool_state_account: &AccountInfo<'a>,
+        divvy_hp_program: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if new_locked_liquidity > current_locked_liquidity {
+            let usdt_amount = new_locked_liquidity
+                                    .checked_sub(current_locked_liquidity)
+                                    .ok_or(ExchangeError::AmountOverflow)?;
+            let instruction = transfer_locked_liquidity(
+                divvy_hp_program.key,
+                token_program.key,
+                pda_account.key,
+                bet_pda_account.key,
+                bet_usdt_account.key,
+                hp_usdt_account.key,
+                pool_state_account.key,
+                usdt_amount,
+                bump_seed,
+            );
+            msg!("Transfer locked liquidity");
+            invoke_signed(
+                    &instruction,
                     &[
-                        bet_usdt_account.clone(),
-                        divvy_foundation_proceeds_usdt.clone(),
-                        pda_account.clone(),
                         token_program.clone(),
-                    ],
-                    &[&[b"divvybetting", &[251]]],
-                )?;
-                msg!("Transfering locked liquidity to house pool");
-                let transfer_instruction = transfer(
-                    &token_program.key,
-                    &bet_usdt_account.key,
-                    &hp_usdt_account.key,
-                    &pda_account.key,
-                    &[&pda_account.key],
-                    (total_house_profit).clone(),
-                )?;
-                invoke_signed(
-                    &transfer_instruction,
-                    &[
+                        pda_account.clone(),
+                        bet_pda_account.clone(),
                         bet_usdt_account.clone(),
                         hp_usdt_account.clone(),
-                        pda_account.clone(),
-                        token_program.clone(),
+                        pool_state_account.clone(),
                     ],
                     &[&[b"divvybetting", &[251]]],
                 )?;
+        } else {
+            let usdt_amount = current_locked_liquidity
+                                    .checked_sub(new_locked_liquidity)
+                                    .ok_or(ExchangeError::AmountOverflow)?;
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &hp_usdt_account.key,
+                &bet_pda_account.key,
+                &[&bet_pda_account.key],
+                usdt_amount
+            )?;
+            msg!("Transferring extra locked liquidity back to house pool");
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    hp_usdt_account.clone(),
+                    bet_usdt_account.clone(),
+                    bet_pda_account.clone(),
+                    token_program.clone(),
+                ],
+                //To Do Please test bump seed thing
+                &[&[b"divvybetting", &[251]]],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns a settled market's remaining balance from the betting pool to the
+    /// house pool, taking the insurance and foundation fees out of any profit.
+    #[allow(clippy::too_many_arguments)]
+    fn distribute_settled_balance<'a>(
+        bettor_balance: u64,
+        locked_liquidity: u64,
+        token_program: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        hp_usdt_account: &AccountInfo<'a>,
+        insurance_fund_usdt_account: &AccountInfo<'a>,
+        divvy_foundation_proceeds_usdt: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if locked_liquidity>=bettor_balance {
+            // House made a loss, return locked liquidity
+            msg!("Transfering locked liquidity to house pool");
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &hp_usdt_account.key,
+                &pda_account.key,
+                &[&pda_account.key],
+                (bettor_balance).clone(),
+            )?;
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    bet_usdt_account.clone(),
+                    hp_usdt_account.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[b"divvybetting", &[251]]],
+            )?;
+        } else {
+            // House made a profit, send 5% to Profit pool and 1% to insurance pool
+            let house_profit_frac: U64F64 = U64F64::from_num(bettor_balance - locked_liquidity);
+            msg!("House profit: {}", house_profit_frac);
+            let insurance_fund_fee: u64 = (house_profit_frac * U64F64::from_num(0.01))
+                .checked_to_num()
+                .ok_or(ExchangeError::AmountOverflow)?;
+            msg!("Insurance fees: {}", insurance_fund_fee);
+            let divvy_foundation_fee: u64 = (house_profit_frac * U64F64::from_num(0.05))
+                .checked_to_num()
+                .ok_or(ExchangeError::AmountOverflow)?;
+            msg!("Foundation fee: {}", divvy_foundation_fee);
+            let total_house_profit: u64 = ((house_profit_frac * U64F64::from_num(0.94)) +  U64F64::from_num(locked_liquidity))
+                .checked_to_num()
+                .ok_or(ExchangeError::AmountOverflow)?;
+            msg!("Total House return: {}", total_house_profit);
+            msg!("Transfering USDT to the insurance fund");
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &insurance_fund_usdt_account.key,
+                &pda_account.key,
+                &[&pda_account.key],
+                insurance_fund_fee.clone(),
+            )?;
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    bet_usdt_account.clone(),
+                    insurance_fund_usdt_account.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[b"divvybetting", &[251]]],
+            )?;
+            msg!("Transfering USDT to the Divvy foundation");
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &divvy_foundation_proceeds_usdt.key,
+                &pda_account.key,
+                &[&pda_account.key],
+                divvy_foundation_fee.clone(),
+            )?;
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    bet_usdt_account.clone(),
+                    divvy_foundation_proceeds_usdt.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[b"divvybetting", &[251]]],
+            )?;
+            msg!("Transfering locked liquidity to house pool");
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &hp_usdt_account.key,
+                &pda_account.key,
+                &[&pda_account.key],
+                (total_house_profit).clone(),
+            )?;
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    bet_usdt_account.clone(),
+                    hp_usdt_account.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[b"divvybetting", &[251]]],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn process_settle_pnl(
+        accounts: &[AccountInfo],
+        bump_seed: u8,
+        program_id: &Pubkey
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let insurance_fund_usdt_account = next_account_info(accounts_iter)?;
+        let divvy_foundation_proceeds_usdt = next_account_info(accounts_iter)?;
+        let event_group_account = next_account_info(accounts_iter)?;
+        // Unpack token accounts to verify their length
+        msg!("Check token account accounts length");
+        TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
+        TokenAccount::unpack(&bet_usdt_account.data.borrow())?;
+        TokenAccount::unpack(&insurance_fund_usdt_account.data.borrow())?;
+        TokenAccount::unpack(&divvy_foundation_proceeds_usdt.data.borrow())?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+        let usd_state = TokenAccount::unpack(&bet_usdt_account.data.borrow())?;
+
+        if *insurance_fund_usdt_account.key != pool_state.insurance_fund_usdt {
+            return Err(ExchangeError::InvalidInsuranceFundUsdtAccount.into());
+        }
+        if *divvy_foundation_proceeds_usdt.key != pool_state.divvy_foundation_proceeds_usdt {
+            return Err(ExchangeError::InvalidDivvyFoundationUsdtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        // Checking bet pool usdt account
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        msg!("Check market status");
+        market_state.result.transition(MarketOutcome::Settled)?;
+        if Clock::get()?.slot < market_state.dispute_end_slot {
+            return Err(ExchangeError::DisputeWindowOpen.into());
+        }
+        msg!("Market Bettor balance: {}", market_state.bettor_balance);
+        msg!("Market locked liquidity: {}",  market_state.locked_liquidity);
+        if market_state.pending_bets == 0 {
+            Self::distribute_settled_balance(
+                market_state.bettor_balance,
+                market_state.locked_liquidity,
+                token_program,
+                pda_account,
+                bet_usdt_account,
+                hp_usdt_account,
+                insurance_fund_usdt_account,
+                divvy_foundation_proceeds_usdt,
+            )?;
+            // msg!("Market pending bets are settled. Asserting.");
+            // if market_state.market_sides[market_state.result as usize].risk != 0 {
+            //     return Err(ExchangeError::MarketSideRiskRemaining.into());
+            // }
+            // if market_state.market_sides[market_state.result as usize].payout != 0 {
+            //     return Err(ExchangeError::MarketSidePayoutRemaining.into());
+            // }
+            // if market_state.bettor_balance != 0 {
+            //     return Err(ExchangeError::MarketBettorBalanceRemaining.into());
+            // }
+            pool_state.live_liquidity = pool_state
+                                        .live_liquidity
+                                        .checked_sub(market_state.locked_liquidity)
+                                        .ok_or(ExchangeError::AmountOverflow)?;
+            if market_state.locked_liquidity > 0 {
+                Self::update_event_liquidity(
+                    event_group_account,
+                    &market_state.metadata,
+                    market_state.locked_liquidity,
+                    0,
+                    u64::MAX,
+                    program_id,
+                )?;
+            }
+            // The remaining bettor balance was paid out above
+            market_state.bettor_balance = 0;
+        } else {
+            return Err(ExchangeError::MarketSideRiskRemaining.into());
+        }
+        //Assert that when all of the house pool pending bets are settled there is
+        //no remaining bettor balance in the house pool.
+        // msg!("Transfering locked liquidity to house pool");
+        // let transfer_instruction = transfer(
+        //     &token_program.key,
+        //     &bet_usdt_account.key,
+        //     &hp_usdt_account.key,
+        //     &pda_account.key,
+        //     &[&pda_account.key],
+        //     usd_state.amount
+        // )?;
+        // invoke_signed(
+        //     &transfer_instruction,
+        //     &[
+        //         bet_usdt_account.clone(),
+        //         hp_usdt_account.clone(),
+        //         pda_account.clone(),
+        //         token_program.clone(),
+        //     ],
+        //     &[&[b"divvybetting", &[251]]],
+        // )?;
+        if pool_state.pending_bets == 0 {
+            // msg!("House pool pending bets are settled. Asserting.");
+            // if pool_state.locked_liquidity != 0 {
+            //     return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
+            // }
+            // if pool_state.live_liquidity != 0 {
+            //     return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
+            // }
+        }
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_settle_bet(
+        accounts: &[AccountInfo],
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let user_main_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let insurance_fund_usdt_account = next_account_info(accounts_iter)?;
+        let divvy_foundation_proceeds_usdt = next_account_info(accounts_iter)?;
+        let user_exposure_account = next_account_info(accounts_iter)?;
+        // Unpack token accounts to verify their length
+        msg!("Check token account accounts length");
+        TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
+        TokenAccount::unpack(&bet_usdt_account.data.borrow())?;
+        TokenAccount::unpack(&insurance_fund_usdt_account.data.borrow())?;
+        TokenAccount::unpack(&divvy_foundation_proceeds_usdt.data.borrow())?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+        let mut bet_state = Bet::unpack(&bet_state_account.data.borrow())?;
+
+        if *insurance_fund_usdt_account.key != pool_state.insurance_fund_usdt {
+            return Err(ExchangeError::InvalidInsuranceFundUsdtAccount.into());
+        }
+        if *divvy_foundation_proceeds_usdt.key != pool_state.divvy_foundation_proceeds_usdt {
+            return Err(ExchangeError::InvalidDivvyFoundationUsdtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        // Checking bet pool usdt account
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+
+        if bet_state.market != *market_state_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        if market_state.result == MarketOutcome::Voided {
+            return Err(ExchangeError::MarketVoided.into());
+        }
+        // Bets settle once the result is in and before the market's PnL is settled
+        if !market_state.result.can_transition_to(MarketOutcome::Settled) {
+            return Err(ExchangeError::InvalidMarketStatus.into());
+        }
+        if Clock::get()?.slot < market_state.dispute_end_slot {
+            return Err(ExchangeError::DisputeWindowOpen.into());
+        }
+
+        if bet_state.user_usdt_account != *user_usdt_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+
+        if bet_state.user_main_account != *user_main_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+
+        if bet_state.outcome != 0 {
+            return Err(ExchangeError::BetAlreadySettled.into());
+        }
+
+        if bet_state.pending_acceptance {
+            msg!("Live bet was never accepted, refunding risk");
+            Self::remove_user_exposure(user_exposure_account, &bet_state, program_id)?;
+            Self::refund_unbooked_bet(
+                &mut market_state,
+                &mut pool_state,
+                &mut bet_state,
+                token_program,
+                pda_account,
+                bet_usdt_account,
+                user_usdt_account,
+            )?;
+            //Return rent to the user that placed the bet
+            let balance = bet_state_account.lamports();
+            **bet_state_account.try_borrow_mut_lamports()? -= balance;
+            **user_main_account.try_borrow_mut_lamports()? += balance;
+            BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+            Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+            Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
+            return Ok(());
+        }
+
+        // Decrement pending bets
+        msg!("Decrementing market pending bets.");
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        msg!("Decrementing betting pool pending bets.");
+        msg!(" {} ",pool_state.pending_bets);
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        
+        let outcome = bet_outcome(&bet_state, &market_state);
+        bet_state.outcome = outcome.pack();
+        if outcome == BetOutcome::Win && bet_state.bet_type == BetType::MoneyLine {
+            //Remove risk & payout in market side. Only for winning bets, as locked
+            // liquidity was already calculated for losers. Other bet types
+            // never entered the market sides.
+            let current_market_side_risk =
+            market_state.market_sides[bet_state.user_market_side as usize].risk;
+            let current_market_side_payout =
+                market_state.market_sides[bet_state.user_market_side as usize].payout;
+            market_state.market_sides[bet_state.user_market_side as usize].risk =
+                current_market_side_risk
+                    .checked_sub(bet_state.user_risk)
+                    .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
+            market_state.market_sides[bet_state.user_market_side as usize].payout =
+                current_market_side_payout
+                    .checked_sub(bet_state.user_payout)
+                    .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
+        }
+
+        // The risk and payout back for a win, the risk back for a push or void,
+        // half of either on split Asian handicap lines and nothing on a loss
+        let bet_balance = outcome
+            .bettor_amount(bet_state.user_risk, bet_state.user_payout)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        if bet_balance > 0 {
+            // Subtract bettor balance in the market. Whatever is left once every
+            // bet is settled goes back to the house when the market's PnL settles.
+            market_state.bettor_balance = market_state
+                .bettor_balance
+                .checked_sub(bet_balance)
+                .ok_or(ExchangeError::AmountOverflow)?;
+
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &user_usdt_account.key,
+                &pda_account.key,
+                &[&pda_account.key],
+                bet_balance,
+            )?;
+            msg!("Calling the token program to transfer the bet balance to user.");
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    user_usdt_account.clone(),
+                    bet_usdt_account.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                //To Do Please test bump seed thing
+                &[&[b"divvybetting", &[251]]],
+            )?;
+        }
+        //Return rent to the user that placed the bet
+        let balance = bet_state_account.lamports();
+        **bet_state_account.try_borrow_mut_lamports()? -= balance;
+        **user_main_account.try_borrow_mut_lamports()? += balance;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_void_market(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let event_group_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+
+        msg!("Check market status");
+        let commenced = market_state.result == MarketOutcome::Commenced;
+        market_state.result.transition(MarketOutcome::Voided)?;
+
+        // Release the market's locked liquidity back to the house pool.
+        // Before commencement it is counted as locked, afterwards as live.
+        let released_liquidity = market_state.locked_liquidity;
+        msg!("- Released liquidity");
+        msg!(0, 0, 0, 0, released_liquidity);
+        if commenced {
+            pool_state.live_liquidity = pool_state
+                .live_liquidity
+                .checked_sub(released_liquidity)
+                .ok_or(ExchangeError::AmountOverflow)?;
+        } else {
+            pool_state.locked_liquidity = pool_state
+                .locked_liquidity
+                .checked_sub(released_liquidity)
+                .ok_or(ExchangeError::AmountOverflow)?;
+        }
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_sub(released_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        market_state.locked_liquidity = 0;
+
+        if released_liquidity > 0 {
+            Self::update_event_liquidity(
+                event_group_account,
+                &market_state.metadata,
+                released_liquidity,
+                0,
+                u64::MAX,
+                program_id,
+            )?;
+            msg!("Transfering locked liquidity to house pool");
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &hp_usdt_account.key,
+                &pda_account.key,
+                &[&pda_account.key],
+                released_liquidity,
+            )?;
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    bet_usdt_account.clone(),
+                    hp_usdt_account.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[b"divvybetting", &[251]]],
+            )?;
+        }
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_refund_bet(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let user_main_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+        let mut bet_state = Bet::unpack(&bet_state_account.data.borrow())?;
+
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if bet_state.market != *market_state_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+        if bet_state.user_usdt_account != *user_usdt_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+        if bet_state.user_main_account != *user_main_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        if market_state.result != MarketOutcome::Voided {
+            return Err(ExchangeError::MarketNotVoided.into());
+        }
+        if bet_state.outcome != 0 {
+            return Err(ExchangeError::BetAlreadySettled.into());
+        }
+
+        // Decrement pending bets
+        msg!("Decrementing market pending bets.");
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("Decrementing betting pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_sub(bet_state.user_risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        //Remove risk & payout in market side, only booked moneyline bets were added to it.
+        if bet_state.bet_type == BetType::MoneyLine && !bet_state.pending_acceptance {
+            let market_side = &mut market_state.market_sides[bet_state.user_market_side as usize];
+            market_side.risk = market_side
+                .risk
+                .checked_sub(bet_state.user_risk)
+                .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
+            market_side.payout = market_side
+                .payout
+                .checked_sub(bet_state.user_payout)
+                .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
+        }
+
+        let transfer_instruction = transfer(
+            &token_program.key,
+            &bet_usdt_account.key,
+            &user_usdt_account.key,
+            &pda_account.key,
+            &[&pda_account.key],
+            bet_state.user_risk,
+        )?;
+        msg!("Calling the token program to refund the risk to user.");
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                user_usdt_account.clone(),
+                bet_usdt_account.clone(),
+                pda_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[b"divvybetting", &[251]]],
+        )?;
+        bet_state.outcome = BetOutcome::Void.pack();
+
+        //Return rent to the user that placed the bet
+        let balance = bet_state_account.lamports();
+        **bet_state_account.try_borrow_mut_lamports()? -= balance;
+        **user_main_account.try_borrow_mut_lamports()? += balance;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_init_market(
+        accounts: &[AccountInfo],
+        start_timestamp: i64,
+        side_count: u8,
+        metadata: MarketMetadata,
+        bump_seed: u8,
+        _program_id: &Pubkey,
+    ) -> ProgramResult {
+        msg!("Checking market side count");
+        if side_count < Market::MIN_SIDES || side_count > Market::MAX_SIDES {
+            return Err(ExchangeError::InvalidMarketSide.into());
+        }
+        msg!("Checking market metadata");
+        if !metadata.is_valid() {
+            return Err(ExchangeError::InvalidMarketMetadata.into());
+        }
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let result_feed_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let market_side_0_odds_feed_account = next_account_info(accounts_iter)?;
+        let market_side_1_odds_feed_account = next_account_info(accounts_iter)?;
+        // Two-way markets have no draw side and no feed for it
+        let market_side_2_odds_feed_account = if side_count == Market::MAX_SIDES {
+            Some(*next_account_info(accounts_iter)?.key)
+        } else {
+            None
+        };
+        let market_side_0_points_feed_account = next_account_info(accounts_iter)?;
+        let market_side_1_points_feed_account = next_account_info(accounts_iter)?;
+        let team_a_score_feed_account = next_account_info(accounts_iter)?;
+        let team_b_score_feed_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        msg!("Unpack pool state");
+        let pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        msg!("Unpack market state");
+        let mut market_state = Market::unpack_unchecked(&market_state_account.data.borrow())?;
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        if market_state.is_initialized {
+            return Err(ExchangeError::MarketAlreadyInitialized.into());
+        }
+        if !Rent::get()?.is_exempt(
+            **market_state_account.lamports.borrow(),
+            market_state_account.data_len(),
+        ) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let market_sides: [MarketSide; 3] = 
+                [
+                    MarketSide {
+                        odds_feed_account: Some(*market_side_0_odds_feed_account.key),
+                        points_feed_account: Some(*market_side_0_points_feed_account.key),
+                        payout: 0,
+                        risk: 0,
+                    },
+                    MarketSide {
+                        odds_feed_account: Some(*market_side_1_odds_feed_account.key),
+                        points_feed_account: Some(*market_side_1_points_feed_account.key),
+                        payout: 0,
+                        risk: 0,
+                    },
+                    MarketSide {
+                        odds_feed_account: market_side_2_odds_feed_account,
+                        points_feed_account: None,
+                        payout: 0,
+                        risk: 0,
+                    },
+                ];
+
+        market_state = Market {
+            is_initialized: true,
+            market_sides: market_sides,
+            locked_liquidity: 0,
+            result_feed: *result_feed_account.key,
+            score_feed_accounts: [*team_a_score_feed_account.key, *team_b_score_feed_account.key],
+            result: MarketOutcome::NotYetCommenced,
+            bettor_balance: 0,
+            pending_bets: 0,
+            team_a_score: 0,
+            team_b_score: 0,
+            total_score: 0,
+            start_timestamp,
+            side_count,
+            metadata,
+            dispute_end_slot: 0,
+            extra_result_oracles: Vec::new(),
+            result_quorum: 1,
+            feed_checks: FeedChecks::DEFAULT,
+            commenced_timestamp: 0,
+            manual_settler: authority::ID,
+            manual_settlement_grace_period: DEFAULT_MANUAL_SETTLEMENT_GRACE_PERIOD,
+            stake_limits: StakeLimits::DEFAULT,
+            suspended: false,
+            live_betting: false,
+            live_acceptance_delay_slots: 0,
+            live_odds_feed_accounts: [None; 3],
+            cash_out: false,
+            cash_out_margin_bps: 0,
+        };
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_init_futures_market(
+        accounts: &[AccountInfo],
+        start_timestamp: i64,
+        side_count: u8,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let result_feed_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        msg!("Unpack pool state");
+        let pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        msg!("Checking market side count");
+        if (side_count as usize) < FuturesMarket::MIN_SIDES
+            || market_state_account.data_len() != FuturesMarket::get_packed_len(side_count)
+        {
+            return Err(ExchangeError::InvalidMarketSide.into());
+        }
+        if FuturesMarket::unpack(&market_state_account.data.borrow()).is_ok() {
+            return Err(ExchangeError::MarketAlreadyInitialized.into());
+        }
+        if !Rent::get()?.is_exempt(
+            **market_state_account.lamports.borrow(),
+            market_state_account.data_len(),
+        ) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let mut market_sides: Vec<MarketSide> = (0..side_count)
+            .map(|_| MarketSide {
+                odds_feed_account: None,
+                points_feed_account: None,
+                payout: 0,
+                risk: 0,
+            })
+            .collect();
+        Self::register_futures_sides(&mut market_sides, accounts_iter)?;
+
+        let market_state = FuturesMarket {
+            is_initialized: true,
+            market_sides,
+            locked_liquidity: 0,
+            result_feed: *result_feed_account.key,
+            result: FuturesOutcome::NotYetCommenced,
+            bettor_balance: 0,
+            pending_bets: 0,
+            start_timestamp,
+            feed_checks: FeedChecks::DEFAULT,
+            stake_limits: StakeLimits::DEFAULT,
+            suspended: false,
+            dispute_end_slot: 0,
+        };
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Binds the remaining odds feed accounts to the sides that have no feed yet, in order.
+    fn register_futures_sides<'a, 'b: 'a>(
+        market_sides: &mut [MarketSide],
+        odds_feed_accounts: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+    ) -> ProgramResult {
+        let mut open_sides = market_sides
+            .iter_mut()
+            .filter(|market_side| market_side.odds_feed_account.is_none());
+        for odds_feed_account in odds_feed_accounts {
+            let market_side = open_sides
+                .next()
+                .ok_or(ExchangeError::InvalidMarketSide)?;
+            market_side.odds_feed_account = Some(*odds_feed_account.key);
+        }
+        Ok(())
+    }
+
+    fn process_add_futures_sides(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
+        if market_state.result != FuturesOutcome::NotYetCommenced {
+            return Err(ExchangeError::MarketCommenced.into());
+        }
+
+        Self::register_futures_sides(&mut market_state.market_sides, accounts_iter)?;
+
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn process_init_futures_bet(
+        accounts: &[AccountInfo],
+        risk: u64,
+        odds: i64,
+        market_side: u8,
+        max_slippage_bps: u16,
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        msg!("- Risk");
+        msg!(0, 0, 0, 0, risk);
+        msg!("- Market side");
+        msg!(0, 0, 0, 0, market_side);
+
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let feed_account = next_account_info(accounts_iter)?;
+        let bet_account = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_pda_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let user_exposure_account = next_account_info(accounts_iter)?;
+        msg!("Validating accounts");
+        msg!("Checking Market state account ownership");
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        msg!("Checking market initialization");
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
+        msg!("Checking pool state initialization");
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::BettingPoolStateNotInitialized))?;
+        msg!("Checking bet state initialization");
+        let bet_state = Bet::unpack_unchecked(&bet_account.data.borrow())?;
+        if bet_state.is_initialized {
+            return Err(ExchangeError::BetAlreadyInitialized.into());
+        }
+        msg!("Checking rent exemption");
+        if !Rent::get()?.is_exempt(**bet_account.lamports.borrow(), bet_account.data_len()) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+        msg!("Unpack House Pool USDC account");
+        let hp_usdt_state = TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
+
+        msg!("Checking house pool usdt account");
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+        }
+
+        msg!("Checking bet pool usdt account");
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+
+        msg!("Checking Token program account ownership");
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+
+        msg!("Checking if betting is frozen");
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+
+        msg!("Checking if market is not commenced or settled yet");
+        if market_state.result != FuturesOutcome::NotYetCommenced {
+            return Err(ExchangeError::MarketCommenced.into());
+        }
+
+        msg!("Checking if market start time has passed");
+        if Clock::get()?.unix_timestamp >= market_state.start_timestamp {
+            return Err(ExchangeError::MarketStarted.into());
+        }
+
+        msg!("Checking if market is suspended");
+        if market_state.suspended {
+            return Err(ExchangeError::MarketSuspended.into());
+        }
+
+        msg!("Checking market side feed account");
+        let side_feed_account = market_state
+            .market_sides
+            .get(market_side as usize)
+            .ok_or(ExchangeError::InvalidMarketSide)?
+            .odds_feed_account
+            .ok_or(ExchangeError::InvalidMarketSide)?;
+        if side_feed_account != *feed_account.key {
+            return Err(ExchangeError::InvalidFeedAccount.into());
+        }
+
+        msg!("Checking if risk is non zero");
+        if risk == 0 {
+            return Err(ExchangeError::BetRiskZero.into());
+        }
+
+        let available_liquidty = calculate_available_liquidity(&hp_usdt_state, &pool_state)?;
+
+        msg!("Getting odds from the Switchboard");
+        let feed_odds = Self::read_feed(feed_account, &market_state.feed_checks)?;
+
+        let payout = calculate_payout(feed_odds, risk).ok_or(ExchangeError::InvalidInstruction)?;
+        msg!("- Bet payout");
+        msg!(0, 0, 0, 0, payout);
+
+        msg!("Checking feed odds against quoted odds");
+        let quoted_payout = calculate_payout(odds as f64, risk).ok_or(ExchangeError::InvalidInstruction)?;
+        if !is_within_slippage(quoted_payout, payout, max_slippage_bps) {
+            return Err(ExchangeError::OddsSlippageExceeded.into());
+        }
+
+        if payout > available_liquidty {
+            return Err(ExchangeError::NotEnoughAvailableLiquidityForBet.into());
+        }
+        if payout == 0u64 {
+            return Err(ExchangeError::PayoutZero.into());
+        }
+        Self::add_user_exposure(
+            user_exposure_account,
+            initializer.key,
+            market_state_account.key,
+            &market_state.stake_limits,
+            risk,
+            payout,
+            program_id,
+        )?;
+
+        msg!("Incrementing market pending bets.");
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_add(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        msg!("Incrementing house pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_add(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        //Add risk & payout in market side and recalculate locked liquidity
+        let side = &mut market_state.market_sides[market_side as usize];
+        side.risk = side
+            .risk
+            .checked_add(risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        side.payout = side
+            .payout
+            .checked_add(payout)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        let new_locked_liquidity = calculate_sides_locked_liquidity(&market_state.market_sides)?;
+        let current_locked_liquidity = market_state.locked_liquidity;
+
+        msg!("Checking market liquidity ceiling");
+        let market_liquidity_ceiling = calculate_liquidity_ceiling(
+            available_liquidty,
+            &pool_state,
+            pool_state.max_market_liquidity_bps,
+        )?;
+        if new_locked_liquidity > current_locked_liquidity
+            && new_locked_liquidity > market_liquidity_ceiling
+        {
+            return Err(ExchangeError::MarketLiquidityCeilingExceeded.into());
+        }
+
+        let current_pool_locked_liquidity = pool_state.locked_liquidity;
+
+        market_state.locked_liquidity = new_locked_liquidity;
+        pool_state.locked_liquidity = current_pool_locked_liquidity
+            .checked_sub(current_locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?
+            .checked_add(new_locked_liquidity)
+            .ok_or(ExchangeError::NotEnoughAvailableLiquidityForBet)?;
+
+        msg!("- Market locked liquidity from");
+        msg!(0, 0, 0, 0, current_locked_liquidity);
+        msg!("- Market locked liquidity to");
+        msg!(0, 0, 0, 0, new_locked_liquidity);
+
+        //Transfer USDT from user account to bet pool account
+        let transfer_instruction = transfer(
+            &token_program.key,
+            &user_usdt_account.key,
+            &bet_usdt_account.key,
+            &initializer.key,
+            &[&initializer.key],
+            risk,
+        )?;
+        msg!("Transferring risk from user account to divvy account");
+        invoke(
+            &transfer_instruction,
+            &[
+                user_usdt_account.clone(),
+                bet_usdt_account.clone(),
+                initializer.clone(),
+                token_program.clone(),
+            ],
+        )?;
+        Self::rebalance_locked_liquidity(
+            current_locked_liquidity,
+            new_locked_liquidity,
+            bump_seed,
+            token_program,
+            pda_account,
+            bet_pda_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            pool_state_account,
+            divvy_hp_program,
+        )?;
+
+        // Futures bets are always on a single side winning outright
+        let bet_state = Bet {
+            is_initialized: true,
+            market: *market_state_account.key,
+            user_usdt_account: *user_usdt_account.key,
+            user_main_account: *initializer.key,
+            user_risk: risk,
+            user_payout: payout,
+            points: 0,
+            user_market_side: market_side,
+            outcome: 0, //Outcome 0 as market not settled.
+            bet_type: BetType::MoneyLine,
+            pending_acceptance: false,
+            accept_slot: 0,
+        };
+
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_add(risk)
+            .and_then(|balance| balance.checked_add(new_locked_liquidity))
+            .and_then(|balance| balance.checked_sub(current_locked_liquidity))
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        // Write the accounts
+        Bet::pack(bet_state, &mut bet_account.data.borrow_mut())?;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_commence_futures_market(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        msg!("Check if initializer is a signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *bet_pool_state_account.owner != *program_id {
+            return Err(ExchangeError::BettingPoolStateNotInitialized.into());
+        }
+
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+
+        // Anyone can crank the market once its start time has passed
+        msg!("Check authority");
+        if Clock::get()?.unix_timestamp < market_state.start_timestamp
+            && initializer.key != &authority::ID
+        {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        msg!("Check market commence status");
+        market_state.result.transition(FuturesOutcome::Commenced)?;
+        pool_state.locked_liquidity = pool_state
+            .locked_liquidity
+            .checked_sub(market_state.locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        pool_state.live_liquidity = pool_state
+            .live_liquidity
+            .checked_add(market_state.locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_settle_futures_market(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let result_account = next_account_info(accounts_iter)?;
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+        let pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        //Verifying result account
+        if result_account.key != &market_state.result_feed {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if market_state.result != FuturesOutcome::Commenced {
+            return Err(ExchangeError::InvalidMarketStatus.into());
+        }
+        //Getting results from Switchboard
+        msg!("Reading result feed");
+        let result = Self::read_feed(result_account, &market_state.feed_checks)?;
+        // The feed reports a side index, anything fractional is a bad round
+        if result < 0f64 || result >= market_state.market_sides.len() as f64 || result.fract() != 0f64 {
+            return Err(ExchangeError::NotValidMarketResult.into());
+        }
+        let winning_side = result as u8;
+        msg!("- Winning side");
+        msg!(0, 0, 0, 0, winning_side);
+
+        market_state.result.transition(FuturesOutcome::SideWon(winning_side))?;
+        market_state.dispute_end_slot = Clock::get()?
+            .slot
+            .checked_add(DISPUTE_WINDOW_SLOTS)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Dispute window ends at slot");
+        msg!(0, 0, 0, 0, market_state.dispute_end_slot);
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_settle_futures_bet(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let user_main_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+        let mut bet_state = Bet::unpack(&bet_state_account.data.borrow())?;
+
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if bet_state.market != *market_state_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        let winning_side = match market_state.result {
+            FuturesOutcome::SideWon(side) => side,
+            _ => return Err(ExchangeError::MarketNotSettled.into()),
+        };
+        if Clock::get()?.slot < market_state.dispute_end_slot {
+            return Err(ExchangeError::DisputeWindowOpen.into());
+        }
+        if bet_state.user_usdt_account != *user_usdt_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+        if bet_state.user_main_account != *user_main_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+        if bet_state.outcome != 0 {
+            return Err(ExchangeError::BetAlreadySettled.into());
+        }
+
+        msg!("Decrementing market pending bets.");
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        msg!("Decrementing betting pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        if bet_state.user_market_side == winning_side {
+            // User won
+            bet_state.outcome = BetOutcome::Win.pack();
+            let bet_balance = bet_state
+                .user_risk
+                .checked_add(bet_state.user_payout)
+                .ok_or(ExchangeError::AmountOverflow)?;
+            market_state.bettor_balance = market_state
+                .bettor_balance
+                .checked_sub(bet_balance)
+                .ok_or(ExchangeError::AmountOverflow)?;
+
+            let side = &mut market_state.market_sides[winning_side as usize];
+            side.risk = side
+                .risk
+                .checked_sub(bet_state.user_risk)
+                .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
+            side.payout = side
+                .payout
+                .checked_sub(bet_state.user_payout)
+                .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
+
+            let transfer_instruction = transfer(
+                &token_program.key,
+                &bet_usdt_account.key,
+                &user_usdt_account.key,
+                &pda_account.key,
+                &[&pda_account.key],
+                bet_balance,
+            )?;
+            msg!("Calling the token program to transfer winnings to user.");
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    user_usdt_account.clone(),
+                    bet_usdt_account.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[b"divvybetting", &[251]]],
+            )?;
+        } else {
+            bet_state.outcome = BetOutcome::Loss.pack();
+        }
+
+        //Return rent to the user that placed the bet
+        let balance = bet_state_account.lamports();
+        **bet_state_account.try_borrow_mut_lamports()? -= balance;
+        **user_main_account.try_borrow_mut_lamports()? += balance;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_settle_futures_pnl(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let insurance_fund_usdt_account = next_account_info(accounts_iter)?;
+        let divvy_foundation_proceeds_usdt = next_account_info(accounts_iter)?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+
+        if *insurance_fund_usdt_account.key != pool_state.insurance_fund_usdt {
+            return Err(ExchangeError::InvalidInsuranceFundUsdtAccount.into());
+        }
+        if *divvy_foundation_proceeds_usdt.key != pool_state.divvy_foundation_proceeds_usdt {
+            return Err(ExchangeError::InvalidDivvyFoundationUsdtAccount.into());
+        }
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        market_state.result.transition(FuturesOutcome::Settled)?;
+        if Clock::get()?.slot < market_state.dispute_end_slot {
+            return Err(ExchangeError::DisputeWindowOpen.into());
+        }
+        if market_state.pending_bets != 0 {
+            return Err(ExchangeError::MarketSideRiskRemaining.into());
+        }
+        msg!("Market Bettor balance: {}", market_state.bettor_balance);
+        msg!("Market locked liquidity: {}", market_state.locked_liquidity);
+        Self::distribute_settled_balance(
+            market_state.bettor_balance,
+            market_state.locked_liquidity,
+            token_program,
+            pda_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            insurance_fund_usdt_account,
+            divvy_foundation_proceeds_usdt,
+        )?;
+        pool_state.live_liquidity = pool_state
+            .live_liquidity
+            .checked_sub(market_state.locked_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        // The remaining bettor balance was paid out above
+        market_state.bettor_balance = 0;
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_void_futures_market(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+
+        msg!("Check market status");
+        let commenced = market_state.result == FuturesOutcome::Commenced;
+        market_state.result.transition(FuturesOutcome::Voided)?;
+
+        // Release the market's locked liquidity back to the house pool.
+        // Before commencement it is counted as locked, afterwards as live.
+        let released_liquidity = market_state.locked_liquidity;
+        msg!("- Released liquidity");
+        msg!(0, 0, 0, 0, released_liquidity);
+        if commenced {
+            pool_state.live_liquidity = pool_state
+                .live_liquidity
+                .checked_sub(released_liquidity)
+                .ok_or(ExchangeError::AmountOverflow)?;
+        } else {
+            pool_state.locked_liquidity = pool_state
+                .locked_liquidity
+                .checked_sub(released_liquidity)
+                .ok_or(ExchangeError::AmountOverflow)?;
+        }
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_sub(released_liquidity)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        market_state.locked_liquidity = 0;
+
+        if released_liquidity > 0 {
+            msg!("Transfering locked liquidity to house pool");
+            let transfer_instruction = transfer(
+                token_program.key,
+                bet_usdt_account.key,
+                hp_usdt_account.key,
+                pda_account.key,
+                &[pda_account.key],
+                released_liquidity,
+            )?;
+            invoke_signed(
+                &transfer_instruction,
+                &[
+                    bet_usdt_account.clone(),
+                    hp_usdt_account.clone(),
+                    pda_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[b"divvybetting", &[251]]],
+            )?;
+        }
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_refund_futures_bet(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_state_account = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let user_main_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+        let mut bet_state = Bet::unpack(&bet_state_account.data.borrow())?;
+
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if bet_state.market != *market_state_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+        if bet_state.user_usdt_account != *user_usdt_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+        if bet_state.user_main_account != *user_main_account.key {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        if market_state.result != FuturesOutcome::Voided {
+            return Err(ExchangeError::MarketNotVoided.into());
+        }
+        if bet_state.outcome != 0 {
+            return Err(ExchangeError::BetAlreadySettled.into());
+        }
+
+        msg!("Decrementing market pending bets.");
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("Decrementing betting pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_sub(bet_state.user_risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        //Remove risk & payout in market side, every futures bet was added to its side
+        let side = market_state
+            .market_sides
+            .get_mut(bet_state.user_market_side as usize)
+            .ok_or(ExchangeError::InvalidMarketSide)?;
+        side.risk = side
+            .risk
+            .checked_sub(bet_state.user_risk)
+            .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
+        side.payout = side
+            .payout
+            .checked_sub(bet_state.user_payout)
+            .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
+
+        let transfer_instruction = transfer(
+            token_program.key,
+            bet_usdt_account.key,
+            user_usdt_account.key,
+            pda_account.key,
+            &[pda_account.key],
+            bet_state.user_risk,
+        )?;
+        msg!("Calling the token program to refund the risk to user.");
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                user_usdt_account.clone(),
+                bet_usdt_account.clone(),
+                pda_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[b"divvybetting", &[251]]],
+        )?;
+        bet_state.outcome = BetOutcome::Void.pack();
+
+        //Return rent to the user that placed the bet
+        let balance = bet_state_account.lamports();
+        **bet_state_account.try_borrow_mut_lamports()? -= balance;
+        **user_main_account.try_borrow_mut_lamports()? += balance;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Tells a futures market account from a moneyline one by its account type.
+    fn is_futures_market(market_state_account: &AccountInfo) -> Result<bool, ProgramError> {
+        match AccountType::of(&market_state_account.data.borrow())? {
+            AccountType::Market | AccountType::Legacy => Ok(false),
+            AccountType::FuturesMarket => Ok(true),
+            _ => Err(ExchangeError::InvalidMarketAccount.into()),
+        }
+    }
+
+    fn process_close_market(
+        accounts: &[AccountInfo],
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+
+        let (closable, pending_bets, bettor_balance) = if !Self::is_futures_market(market_state_account)? {
+            let market_state = Market::unpack(&market_state_account.data.borrow())?;
+            (
+                market_state.result == MarketOutcome::Settled
+                    || market_state.result == MarketOutcome::Voided,
+                market_state.pending_bets,
+                market_state.bettor_balance,
+            )
+        } else {
+            let market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+            (
+                market_state.result == FuturesOutcome::Settled
+                    || market_state.result == FuturesOutcome::Voided,
+                market_state.pending_bets,
+                market_state.bettor_balance,
+            )
+        };
+        msg!("Checking market is settled");
+        if !closable {
+            return Err(ExchangeError::MarketNotSettled.into());
+        }
+        if pending_bets != 0 {
+            return Err(ExchangeError::MarketBetsPending.into());
+        }
+        if bettor_balance != 0 {
+            return Err(ExchangeError::MarketBettorBalanceRemaining.into());
+        }
+
+        msg!("Returning market rent to the authority");
+        let balance = market_state_account.lamports();
+        **market_state_account.try_borrow_mut_lamports()? -= balance;
+        **initializer.try_borrow_mut_lamports()? += balance;
+        // Zero the data so the account can not be read as a market again
+        for byte in market_state_account.data.borrow_mut().iter_mut() {
+            *byte = 0;
+        }
+
+        Ok(())
+    }
+
+    fn process_override_result(
+        accounts: &[AccountInfo],
+        winning_side: u8,
+        team_a_score: u16,
+        team_b_score: u16,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+
+        if Self::is_futures_market(market_state_account)? {
+            let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+            msg!("Check market has a proposed result");
+            let previous_side = match market_state.result {
+                FuturesOutcome::SideWon(side) => side,
+                _ => return Err(ExchangeError::InvalidMarketStatus.into()),
+            };
+            msg!("Check dispute window");
+            if Clock::get()?.slot >= market_state.dispute_end_slot {
+                return Err(ExchangeError::DisputeWindowClosed.into());
+            }
+            if winning_side as usize >= market_state.market_sides.len() {
+                return Err(ExchangeError::NotValidMarketResult.into());
+            }
+            msg!("- Previous winning side");
+            msg!(0, 0, 0, 0, previous_side);
+            market_state.result = FuturesOutcome::SideWon(winning_side);
+            msg!("- Overridden winning side");
+            msg!(0, 0, 0, 0, winning_side);
+            market_state.pack(&mut market_state_account.data.borrow_mut())?;
+            return Ok(());
+        }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+
+        msg!("Check market has a proposed result");
+        if !market_state.result.can_transition_to(MarketOutcome::Settled) {
+            return Err(ExchangeError::InvalidMarketStatus.into());
+        }
+        msg!("Check dispute window");
+        if Clock::get()?.slot >= market_state.dispute_end_slot {
+            return Err(ExchangeError::DisputeWindowClosed.into());
+        }
+        if winning_side >= market_state.side_count {
+            return Err(ExchangeError::NotValidMarketResult.into());
+        }
+
+        msg!("- Previous result");
+        msg!(market_state.result.into());
+        market_state.result = MarketOutcome::side_won(winning_side).ok_or(ExchangeError::NotValidMarketResult)?;
+        market_state.team_a_score = team_a_score;
+        market_state.team_b_score = team_b_score;
+        market_state.total_score = team_a_score
+            .checked_add(team_b_score)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Overridden result");
+        msg!(market_state.result.into());
+
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_configure_result_oracles(
+        accounts: &[AccountInfo],
+        result_quorum: u8,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+
+        msg!("Check market is awaiting its result");
+        if market_state.result != MarketOutcome::NotYetCommenced
+            && market_state.result != MarketOutcome::Commenced
+        {
+            return Err(ExchangeError::InvalidMarketStatus.into());
+        }
+
+        let mut extra_result_oracles = Vec::with_capacity(Market::MAX_EXTRA_RESULT_ORACLES);
+        while let Some(result_feed_account) = accounts_iter.next() {
+            if extra_result_oracles.len() == Market::MAX_EXTRA_RESULT_ORACLES {
+                return Err(ExchangeError::InvalidInstruction.into());
+            }
+            extra_result_oracles.push(ResultOracle {
+                result_feed: *result_feed_account.key,
+                team_a_score_feed: *next_account_info(accounts_iter)?.key,
+                team_b_score_feed: *next_account_info(accounts_iter)?.key,
+            });
+        }
+
+        // A majority is required so two conflicting results can never both reach quorum
+        let oracle_count = 1 + extra_result_oracles.len();
+        msg!("Checking result quorum");
+        if result_quorum as usize > oracle_count || (result_quorum as usize) * 2 <= oracle_count {
+            return Err(ExchangeError::InvalidOracleQuorum.into());
+        }
+
+        market_state.extra_result_oracles = extra_result_oracles;
+        market_state.result_quorum = result_quorum;
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Reads the latest value of a Switchboard feed, rejecting rounds that are
+    /// older or have fewer oracle responses than the market accepts.
+    fn read_feed(feed_account: &AccountInfo, feed_checks: &FeedChecks) -> Result<f64, ProgramError> {
+        let aggregator: AggregatorState = get_aggregator(feed_account)?;
+        let round_result: RoundResult = get_aggregator_result(&aggregator)?;
+
+        let responses = round_result.num_success.unwrap_or(0).max(0) as u32;
+        if responses < feed_checks.min_oracle_responses {
+            msg!("- Oracle responses");
+            msg!(0, 0, 0, 0, responses);
+            return Err(ExchangeError::InsufficientOracleResponses.into());
+        }
+        let round_open_slot = round_result
+            .round_open_slot
+            .ok_or(ExchangeError::FeedNotInitialized)?;
+        let feed_age_slots = Clock::get()?.slot.saturating_sub(round_open_slot);
+        if feed_age_slots > feed_checks.max_feed_age_slots {
+            msg!("- Feed age in slots");
+            msg!(0, 0, 0, 0, feed_age_slots);
+            return Err(ExchangeError::StaleFeed.into());
+        }
+
+        Ok(round_result
+            .result
+            .ok_or(ExchangeError::FeedNotInitialized)?)
+    }
+
+    /// Whether `points` is within the market's tolerance of the line reported
+    /// by the points feed of `market_side`.
+    fn line_matches_feed(
+        points_feed_account: &AccountInfo,
+        market_state: &Market,
+        market_side: u8,
+        points: i32,
+    ) -> Result<bool, ProgramError> {
+        msg!("Checking points feed account");
+        if market_state.market_sides[market_side as usize].points_feed_account
+            != Some(*points_feed_account.key)
+        {
+            return Err(ExchangeError::InvalidFeedAccount.into());
+        }
+        let feed_line = Self::read_feed(points_feed_account, &market_state.feed_checks)?;
+        let feed_points = points_from_feed(feed_line).ok_or(ExchangeError::LineMismatch)?;
+        let deviation = (i64::from(points) - i64::from(feed_points)).unsigned_abs();
+        Ok(deviation <= u64::from(market_state.feed_checks.max_line_deviation))
+    }
+
+    fn process_configure_feed_checks(
+        accounts: &[AccountInfo],
+        feed_checks: FeedChecks,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+
+        if !Self::is_futures_market(market_state_account)? {
+            let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+            market_state.feed_checks = feed_checks;
+            Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        } else {
+            let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+            market_state.feed_checks = feed_checks;
+            market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        }
+        Ok(())
+    }
+
+    /// Reads the winning side and team scores reported by one result oracle.
+    /// A winning side outside the market's `side_count` sides is refused.
+    fn read_result_oracle(
+        oracle: &ResultOracle,
+        feed_checks: &FeedChecks,
+        side_count: u8,
+        result_account: &AccountInfo,
+        teama_points_account: &AccountInfo,
+        teamb_points_account: &AccountInfo,
+    ) -> Result<(u8, u16, u16), ProgramError> {
+        if *result_account.key != oracle.result_feed
+            || *teama_points_account.key != oracle.team_a_score_feed
+            || *teamb_points_account.key != oracle.team_b_score_feed
+        {
+            return Err(ExchangeError::InvalidFeedAccount.into());
+        }
+        let result = Self::read_feed(result_account, feed_checks)?;
+        // The feed reports a side index, anything fractional is a bad round
+        if result < 0f64 || result >= f64::from(side_count) || result.fract() != 0f64 {
+            return Err(ExchangeError::NotValidMarketResult.into());
+        }
+        let result_u8 = result as u8;
+        let teama_u16 = Self::read_feed(teama_points_account, feed_checks)? as u16;
+        let teamb_u16 = Self::read_feed(teamb_points_account, feed_checks)? as u16;
+        msg!("- Oracle result, team A score, team B score");
+        msg!(0, 0, result_u8, teama_u16, teamb_u16);
+        Ok((result_u8, teama_u16, teamb_u16))
+    }
+
+    fn process_configure_stake_limits(
+        accounts: &[AccountInfo],
+        stake_limits: StakeLimits,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if stake_limits.min_stake == 0 || stake_limits.min_stake > stake_limits.max_stake {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+
+        if !Self::is_futures_market(market_state_account)? {
+            let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+            market_state.stake_limits = stake_limits;
+            Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        } else {
+            let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+            market_state.stake_limits = stake_limits;
+            market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        }
+        Ok(())
+    }
+
+    fn process_suspend_market(
+        accounts: &[AccountInfo],
+        suspend: bool,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+
+        if suspend {
+            msg!("Suspending betting on market");
+        } else {
+            msg!("Resuming betting on market");
+        }
+
+        if !Self::is_futures_market(market_state_account)? {
+            let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+            market_state.suspended = suspend;
+            Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        } else {
+            let mut market_state = FuturesMarket::unpack(&market_state_account.data.borrow())?;
+            market_state.suspended = suspend;
+            market_state.pack(&mut market_state_account.data.borrow_mut())?;
+        }
+        Ok(())
+    }
+
+    fn process_configure_live_betting(
+        accounts: &[AccountInfo],
+        live_betting: bool,
+        acceptance_delay_slots: u64,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
+
+        if live_betting {
+            msg!("Registering live odds feeds");
+            let mut live_odds_feed_accounts = [None; 3];
+            for live_odds_feed_account in live_odds_feed_accounts
+                .iter_mut()
+                .take(market_state.side_count as usize)
+            {
+                *live_odds_feed_account = Some(*next_account_info(accounts_iter)?.key);
+            }
+            market_state.live_odds_feed_accounts = live_odds_feed_accounts;
+        }
+        market_state.live_betting = live_betting;
+        market_state.live_acceptance_delay_slots = acceptance_delay_slots;
+        msg!("- Live bet acceptance delay");
+        msg!(0, 0, 0, 0, acceptance_delay_slots);
+
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_accept_live_bet(
+        accounts: &[AccountInfo],
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _initializer = next_account_info(accounts_iter)?;
+        let feed_account = next_account_info(accounts_iter)?;
+        let bet_account = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_pda_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let user_exposure_account = next_account_info(accounts_iter)?;
+        let event_group_account = next_account_info(accounts_iter)?;
+        let user_main_account = next_account_info(accounts_iter)?;
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::BettingPoolStateNotInitialized))?;
+        let mut bet_state = Bet::unpack(&bet_account.data.borrow())?;
+        let points_feed_account = if bet_state.bet_type == BetType::MoneyLine {
+            None
+        } else {
+            Some(next_account_info(accounts_iter)?)
+        };
+        let hp_usdt_state = TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
+
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if bet_state.market != *market_state_account.key
+            || bet_state.user_usdt_account != *user_usdt_account.key
+            || bet_state.user_main_account != *user_main_account.key
+        {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        if !bet_state.pending_acceptance || bet_state.outcome != 0 {
+            return Err(ExchangeError::LiveBetNotPending.into());
+        }
+        // Once the market moves on, pending live bets are refunded when they settle
+        msg!("Check market status");
+        if market_state.result != MarketOutcome::Commenced {
+            return Err(ExchangeError::InvalidMarketStatus.into());
+        }
+        if Clock::get()?.slot < bet_state.accept_slot {
+            return Err(ExchangeError::LiveBetAcceptanceDelay.into());
+        }
+        msg!("Checking live odds feed account");
+        if market_state.live_odds_feed_accounts[bet_state.user_market_side as usize]
+            != Some(*feed_account.key)
+        {
+            return Err(ExchangeError::InvalidFeedAccount.into());
+        }
+
+        msg!("Getting odds from the Switchboard");
+        let feed_odds = Self::read_feed(feed_account, &market_state.feed_checks)?;
+        let payout = calculate_payout(feed_odds, bet_state.user_risk)
+            .ok_or(ExchangeError::InvalidInstruction)?;
+        msg!("- Payout at current odds");
+        msg!(0, 0, 0, 0, payout);
+        let line_matches = match points_feed_account {
+            Some(points_feed_account) => Self::line_matches_feed(
+                points_feed_account,
+                &market_state,
+                bet_state.user_market_side,
+                bet_state.points,
+            )?,
+            None => true,
+        };
+
+        // Shorter odds or a moved line mean the event moved before the feed did
+        let available_liquidty = calculate_available_liquidity(&hp_usdt_state, &pool_state)?;
+        if market_state.live_betting
+            && !market_state.suspended
+            && payout >= bet_state.user_payout
+            && line_matches
+            // The house may no longer cover the bet by the time it is accepted
+            && Self::bet_fits_liquidity(
+                &market_state,
+                &pool_state,
+                bet_state.user_risk,
+                bet_state.user_payout,
+                bet_state.user_market_side,
+                bet_state.bet_type,
+                available_liquidty,
+                event_group_account,
+                program_id,
+            )?
+        {
+            msg!("Booking live bet");
+            Self::book_bet(
+                &mut market_state,
+                &mut pool_state,
+                bet_state.user_risk,
+                bet_state.user_payout,
+                bet_state.user_market_side,
+                bet_state.bet_type,
+                available_liquidty,
+                bump_seed,
+                token_program,
+                pda_account,
+                bet_pda_account,
+                bet_usdt_account,
+                hp_usdt_account,
+                pool_state_account,
+                divvy_hp_program,
+                event_group_account,
+                program_id,
+            )?;
+            bet_state.pending_acceptance = false;
+        } else {
+            msg!("Live bet rejected, refunding risk");
+            Self::remove_user_exposure(user_exposure_account, &bet_state, program_id)?;
+            Self::refund_unbooked_bet(
+                &mut market_state,
+                &mut pool_state,
+                &mut bet_state,
+                token_program,
+                pda_account,
+                bet_usdt_account,
+                user_usdt_account,
+            )?;
+            //Return rent to the user that placed the bet
+            let balance = bet_account.lamports();
+            **bet_account.try_borrow_mut_lamports()? -= balance;
+            **user_main_account.try_borrow_mut_lamports()? += balance;
+        }
+
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Bet::pack(bet_state, &mut bet_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_configure_cash_out(
+        accounts: &[AccountInfo],
+        cash_out: bool,
+        cash_out_margin_bps: u16,
+        _bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
+        if cash_out_margin_bps > 10_000 {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+
+        market_state.cash_out = cash_out;
+        market_state.cash_out_margin_bps = cash_out_margin_bps;
+        msg!("- Cash out margin bps");
+        msg!(0, 0, 0, 0, cash_out_margin_bps);
+
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_cash_out(
+        accounts: &[AccountInfo],
+        min_value: u64,
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let feed_account = next_account_info(accounts_iter)?;
+        let bet_account = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_pda_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let user_exposure_account = next_account_info(accounts_iter)?;
+        let event_group_account = next_account_info(accounts_iter)?;
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::BettingPoolStateNotInitialized))?;
+        let mut bet_state = Bet::unpack(&bet_account.data.borrow())?;
+        let points_feed_account = if bet_state.bet_type == BetType::MoneyLine {
+            None
+        } else {
+            Some(next_account_info(accounts_iter)?)
+        };
+        let hp_usdt_state = TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
+
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if bet_state.market != *market_state_account.key
+            || bet_state.user_usdt_account != *user_usdt_account.key
+            || bet_state.user_main_account != *initializer.key
+        {
+            return Err(ExchangeError::ExpectedDataMismatch.into());
+        }
+
+        //Checking if betting is frozen
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        if bet_state.outcome != 0 {
+            return Err(ExchangeError::BetAlreadySettled.into());
+        }
+        // Live bets are only priced once they are booked
+        if bet_state.pending_acceptance || !market_state.cash_out || market_state.suspended {
+            return Err(ExchangeError::CashOutNotAvailable.into());
+        }
+        msg!("Check market status");
+        let live = market_state.result == MarketOutcome::Commenced;
+        let odds_feed_account = if live && market_state.live_betting {
+            market_state.live_odds_feed_accounts[bet_state.user_market_side as usize]
+        } else if market_state.result == MarketOutcome::NotYetCommenced
+            && Clock::get()?.unix_timestamp < market_state.start_timestamp
+        {
+            market_state.market_sides[bet_state.user_market_side as usize].odds_feed_account
+        } else {
+            return Err(ExchangeError::CashOutNotAvailable.into());
+        };
+        msg!("Checking odds feed account");
+        if odds_feed_account != Some(*feed_account.key) {
+            return Err(ExchangeError::InvalidFeedAccount.into());
+        }
+        // The odds only price the bet while its line is still the one on offer
+        if let Some(points_feed_account) = points_feed_account {
+            if !Self::line_matches_feed(
+                points_feed_account,
+                &market_state,
+                bet_state.user_market_side,
+                bet_state.points,
+            )? {
+                return Err(ExchangeError::LineMismatch.into());
+            }
+        }
+
+        msg!("Getting odds from the Switchboard");
+        let feed_odds = Self::read_feed(feed_account, &market_state.feed_checks)?;
+        let decimal_odds = calculate_decimal_odds(feed_odds).ok_or(ExchangeError::InvalidInstruction)?;
+        let value = cash_out_value(
+            bet_state.user_risk,
+            bet_state.user_payout,
+            decimal_odds,
+            market_state.cash_out_margin_bps,
+        )
+        .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Cash out value");
+        msg!(0, 0, 0, 0, value);
+        if value == 0 {
+            return Err(ExchangeError::PayoutZero.into());
+        }
+        if value < min_value {
+            return Err(ExchangeError::OddsSlippageExceeded.into());
+        }
+
+        //Calculating locked liquidity without the bet
+        let mut new_locked_liquidity = if bet_state.bet_type == BetType::MoneyLine {
+            //Remove risk & payout in market side
+            let old_locked_liquidity = calculate_locked_liquidity(&market_state)?;
+            let market_side = &mut market_state.market_sides[bet_state.user_market_side as usize];
+            market_side.risk = market_side
+                .risk
+                .checked_sub(bet_state.user_risk)
+                .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
+            market_side.payout = market_side
+                .payout
+                .checked_sub(bet_state.user_payout)
+                .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
+            let new_moneyline_locked_liquidity = calculate_locked_liquidity(&market_state)?;
+            market_state
+                .locked_liquidity
+                .checked_add(new_moneyline_locked_liquidity)
+                .and_then(|locked_liquidity| locked_liquidity.checked_sub(old_locked_liquidity))
+                .ok_or(ExchangeError::AmountOverflow)?
+        } else {
+            market_state
+                .locked_liquidity
+                .checked_sub(bet_state.user_payout)
+                .ok_or(ExchangeError::AmountOverflow)?
+        };
+        // Whatever the bettor takes on top of the risk is the house's loss,
+        // so that much stays locked until the market's PnL settles
+        new_locked_liquidity = new_locked_liquidity
+            .checked_add(value.saturating_sub(bet_state.user_risk))
+            .ok_or(ExchangeError::AmountOverflow)?;
+        let available_liquidity = calculate_available_liquidity(&hp_usdt_state, &pool_state)?;
+        if new_locked_liquidity.saturating_sub(market_state.locked_liquidity) > available_liquidity {
+            return Err(ExchangeError::NotEnoughAvailableLiquidityForBet.into());
+        }
+        Self::lock_market_liquidity(
+            &mut market_state,
+            &mut pool_state,
+            new_locked_liquidity,
+            available_liquidity,
+            bump_seed,
+            token_program,
+            pda_account,
+            bet_pda_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            pool_state_account,
+            divvy_hp_program,
+            event_group_account,
+            program_id,
+        )?;
+        Self::remove_user_exposure(user_exposure_account, &bet_state, program_id)?;
+
+        msg!("Decrementing market pending bets.");
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("Decrementing betting pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        market_state.bettor_balance = market_state
+            .bettor_balance
+            .checked_sub(value)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        let transfer_instruction = transfer(
+            token_program.key,
+            bet_usdt_account.key,
+            user_usdt_account.key,
+            pda_account.key,
+            &[pda_account.key],
+            value,
+        )?;
+        msg!("Calling the token program to transfer the cash out value to user.");
+        invoke_signed(
+            &transfer_instruction,
+            &[
+                user_usdt_account.clone(),
+                bet_usdt_account.clone(),
+                pda_account.clone(),
+                token_program.clone(),
+            ],
+            &[&[b"divvybetting", &[251]]],
+        )?;
+        bet_state.outcome = Bet::CASHED_OUT;
+
+        //Return rent to the user that placed the bet
+        let balance = bet_account.lamports();
+        **bet_account.try_borrow_mut_lamports()? -= balance;
+        **initializer.try_borrow_mut_lamports()? += balance;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        Bet::pack(bet_state, &mut bet_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Takes a cashed out or rejected live bet off the user's exposure on its
+    /// market.
+    fn remove_user_exposure(
+        user_exposure_account: &AccountInfo,
+        bet_state: &Bet,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        msg!("Checking user exposure account");
+        let expected_exposure_account =
+            user_exposure_address(&bet_state.user_main_account, &bet_state.market, program_id)
+                .map_err(|_| ExchangeError::InvalidUserExposureAccount)?;
+        if *user_exposure_account.key != expected_exposure_account
+            || *user_exposure_account.owner != *program_id
+        {
+            return Err(ExchangeError::InvalidUserExposureAccount.into());
+        }
+        let mut exposure = UserExposure::unpack(&user_exposure_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::InvalidUserExposureAccount))?;
+        exposure.risk = exposure
+            .risk
+            .checked_sub(bet_state.user_risk)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        exposure.payout = exposure
+            .payout
+            .checked_sub(bet_state.user_payout)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        UserExposure::pack(exposure, &mut user_exposure_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    fn process_init_parlay(
+        accounts: &[AccountInfo],
+        risk: u64,
+        max_slippage_bps: u16,
+        legs: &[ParlayLegQuote],
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let parlay_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_pda_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        msg!("Checking pool state initialization");
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::BettingPoolStateNotInitialized))?;
+        Self::check_new_parlay(
+            parlay_account,
+            legs.len(),
+            &pool_state,
+            hp_usdt_account,
+            bet_usdt_account,
+            token_program,
+            risk,
+            program_id,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut parlay_legs: Vec<ParlayLeg> = Vec::with_capacity(legs.len());
+        let mut quoted_legs: Vec<ParlayLeg> = Vec::with_capacity(legs.len());
+        for leg in legs.iter() {
+            let market_state_account = next_account_info(accounts_iter)?;
+            let feed_account = next_account_info(accounts_iter)?;
+            let points_feed_account = if leg.bet_type == BetType::MoneyLine {
+                None
+            } else {
+                Some(next_account_info(accounts_iter)?)
+            };
+            let market_state = Self::open_parlay_leg(
+                market_state_account,
+                points_feed_account,
+                &parlay_legs,
+                leg.market_side,
+                leg.bet_type,
+                leg.points,
+                now,
+                program_id,
+            )?;
+            if market_state.market_sides[leg.market_side as usize].odds_feed_account
+                != Some(*feed_account.key)
+            {
+                return Err(ExchangeError::InvalidFeedAccount.into());
+            }
+
+            let feed_odds = Self::read_feed(feed_account, &market_state.feed_checks)?;
+            let parlay_leg = |decimal_odds| ParlayLeg {
+                market: *market_state_account.key,
+                market_side: leg.market_side,
+                bet_type: leg.bet_type,
+                points: leg.points,
+                decimal_odds,
+                outcome: None,
+            };
+            parlay_legs.push(parlay_leg(
+                calculate_decimal_odds(feed_odds).ok_or(ExchangeError::InvalidInstruction)?,
+            ));
+            quoted_legs.push(parlay_leg(
+                calculate_decimal_odds(leg.odds as f64).ok_or(ExchangeError::InvalidInstruction)?,
+            ));
+            Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        }
+
+        let payout = parlay_bettor_amount(risk, &parlay_legs)
+            .and_then(|amount| amount.checked_sub(risk))
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Parlay payout");
+        msg!(0, 0, 0, 0, payout);
+        let quoted_payout = parlay_bettor_amount(risk, &quoted_legs)
+            .and_then(|amount| amount.checked_sub(risk))
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Quoted payout");
+        msg!(0, 0, 0, 0, quoted_payout);
+        if !is_within_slippage(quoted_payout, payout, max_slippage_bps) {
+            return Err(ExchangeError::OddsSlippageExceeded.into());
+        }
+
+        Self::fund_parlay(
+            &mut pool_state,
+            risk,
+            payout,
+            bump_seed,
+            initializer,
+            user_usdt_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            token_program,
+            pda_account,
+            bet_pda_account,
+            pool_state_account,
+            divvy_hp_program,
+        )?;
+
+        let parlay = Parlay {
+            is_initialized: true,
+            user_usdt_account: *user_usdt_account.key,
+            user_main_account: *initializer.key,
+            user_risk: risk,
+            user_payout: payout,
+            outcome: 0,
+            teaser_config: None,
+            legs: parlay_legs,
+        };
+        parlay.pack(&mut parlay_account.data.borrow_mut())?;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_init_teaser(
+        accounts: &[AccountInfo],
+        risk: u64,
+        legs: &[TeaserLegQuote],
+        bump_seed: u8,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        let parlay_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+        let hp_usdt_account = next_account_info(accounts_iter)?;
+        let bet_usdt_account = next_account_info(accounts_iter)?;
+        let user_usdt_account = next_account_info(accounts_iter)?;
+        let token_program = next_account_info(accounts_iter)?;
+        let pda_account = next_account_info(accounts_iter)?;
+        let bet_pda_account = next_account_info(accounts_iter)?;
+        let pool_state_account = next_account_info(accounts_iter)?;
+        let divvy_hp_program = next_account_info(accounts_iter)?;
+        let teaser_config_account = next_account_info(accounts_iter)?;
+        msg!("Checking pool state initialization");
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::BettingPoolStateNotInitialized))?;
+        Self::check_new_parlay(
+            parlay_account,
+            legs.len(),
+            &pool_state,
+            hp_usdt_account,
+            bet_usdt_account,
+            token_program,
+            risk,
+            program_id,
+        )?;
+        msg!("Checking teaser config");
+        if *teaser_config_account.owner != *program_id {
+            return Err(ExchangeError::InvalidTeaserConfigAccount.into());
+        }
+        let teaser_config = TeaserConfig::unpack(&teaser_config_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::InvalidTeaserConfigAccount))?;
+        if teaser_config.decimal_odds(legs.len()).is_none() {
+            return Err(ExchangeError::InvalidParlayLegCount.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut parlay_legs: Vec<ParlayLeg> = Vec::with_capacity(legs.len());
+        for leg in legs.iter() {
+            let market_state_account = next_account_info(accounts_iter)?;
+            let points_feed_account = next_account_info(accounts_iter)?;
+            if leg.bet_type != BetType::Spread && leg.bet_type != BetType::Total {
+                return Err(ExchangeError::InvalidTeaserLeg.into());
+            }
+            // The line is checked against the feed before it is teased
+            let market_state = Self::open_parlay_leg(
+                market_state_account,
+                Some(points_feed_account),
+                &parlay_legs,
+                leg.market_side,
+                leg.bet_type,
+                leg.points,
+                now,
+                program_id,
+            )?;
+            let teased_points =
+                teased_line(leg.bet_type, leg.market_side, leg.points, teaser_config.teaser_points)
+                    .ok_or(ExchangeError::InvalidTeaserLeg)?;
+            parlay_legs.push(ParlayLeg {
+                market: *market_state_account.key,
+                market_side: leg.market_side,
+                bet_type: leg.bet_type,
+                points: teased_points,
+                decimal_odds: 0,
+                outcome: None,
+            });
+            Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        }
+
+        let payout = teaser_bettor_amount(risk, &parlay_legs, &teaser_config)
+            .and_then(|amount| amount.checked_sub(risk))
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Teaser payout");
+        msg!(0, 0, 0, 0, payout);
+
+        Self::fund_parlay(
+            &mut pool_state,
+            risk,
+            payout,
+            bump_seed,
+            initializer,
+            user_usdt_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            token_program,
+            pda_account,
+            bet_pda_account,
+            pool_state_account,
+            divvy_hp_program,
+        )?;
+
+        let parlay = Parlay {
+            is_initialized: true,
+            user_usdt_account: *user_usdt_account.key,
+            user_main_account: *initializer.key,
+            user_risk: risk,
+            user_payout: payout,
+            outcome: 0,
+            teaser_config: Some(*teaser_config_account.key),
+            legs: parlay_legs,
+        };
+        parlay.pack(&mut parlay_account.data.borrow_mut())?;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Checks the accounts and stake of a new parlay or teaser with `leg_count` legs.
+    #[allow(clippy::too_many_arguments)]
+    fn check_new_parlay(
+        parlay_account: &AccountInfo,
+        leg_count: usize,
+        pool_state: &BettingPoolState,
+        hp_usdt_account: &AccountInfo,
+        bet_usdt_account: &AccountInfo,
+        token_program: &AccountInfo,
+        risk: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        msg!("Checking parlay legs");
+        if !(Parlay::MIN_LEGS..=Parlay::MAX_LEGS).contains(&leg_count) {
+            return Err(ExchangeError::InvalidParlayLegCount.into());
+        }
+        msg!("Checking parlay account");
+        if *parlay_account.owner != *program_id
+            || parlay_account.data_len() != Parlay::get_packed_len(leg_count as u8)
+        {
+            return Err(ExchangeError::InvalidParlayAccount.into());
+        }
+        if Parlay::unpack_unchecked(&parlay_account.data.borrow())?.is_initialized {
+            return Err(ExchangeError::BetAlreadyInitialized.into());
+        }
+        if !Rent::get()?.is_exempt(**parlay_account.lamports.borrow(), parlay_account.data_len()) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
+        }
+        if *bet_usdt_account.key != pool_state.betting_pool_usdt {
+            return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
+        }
+        if *token_program.key != token_program_id::ID {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+        if pool_state.frozen_betting {
+            return Err(ExchangeError::BettingFrozen.into());
+        }
+        if risk == 0 {
+            return Err(ExchangeError::BetRiskZero.into());
+        }
+        Ok(())
+    }
+
+    /// Checks a parlay or teaser leg against its market and counts it as a
+    /// pending bet there. The caller packs the returned market.
+    #[allow(clippy::too_many_arguments)]
+    fn open_parlay_leg(
+        market_state_account: &AccountInfo,
+        points_feed_account: Option<&AccountInfo>,
+        legs: &[ParlayLeg],
+        market_side: u8,
+        bet_type: BetType,
+        points: i32,
+        now: UnixTimestamp,
+        program_id: &Pubkey,
+    ) -> Result<Market, ProgramError> {
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        // Legs on the same market are correlated and would be mispriced
+        if legs.iter().any(|leg| leg.market == *market_state_account.key) {
+            return Err(ExchangeError::DuplicateParlayMarket.into());
+        }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())
+            .map_err(|_| Into::<ProgramError>::into(ExchangeError::MarketNotInitialized))?;
+        if market_state.result != MarketOutcome::NotYetCommenced {
+            return Err(ExchangeError::MarketCommenced.into());
+        }
+        if now >= market_state.start_timestamp {
+            return Err(ExchangeError::MarketStarted.into());
+        }
+        if market_state.suspended {
+            return Err(ExchangeError::MarketSuspended.into());
+        }
+        if market_side >= market_state.side_count {
+            return Err(ExchangeError::InvalidMarketSide.into());
+        }
+        if !is_valid_line(bet_type, points) {
+            return Err(ExchangeError::InvalidBetLine.into());
+        }
+        if let Some(points_feed_account) = points_feed_account {
+            if !Self::line_matches_feed(points_feed_account, &market_state, market_side, points)? {
+                return Err(ExchangeError::LineMismatch.into());
             }
-            // msg!("Market pending bets are settled. Asserting.");
-            // if market_state.market_sides[market_state.result as usize].risk != 0 {
-            //     return Err(ExchangeError::MarketSideRiskRemaining.into());
-            // }
-            // if market_state.market_sides[market_state.result as usize].payout != 0 {
-            //     return Err(ExchangeError::MarketSidePayoutRemaining.into());
-            // }
-            // if market_state.bettor_balance != 0 {
-            //     return Err(ExchangeError::MarketBettorBalanceRemaining.into());
-            // }
-            pool_state.live_liquidity = pool_state
-                                        .live_liquidity
-                                        .checked_sub(market_state.locked_liquidity)
-                                        .ok_or(ExchangeError::AmountOverflow)?;
-            market_state.result = MarketOutcome::Settled;
-        } else {
-            return Err(ExchangeError::MarketSideRiskRemaining.into());
         }
-        //Assert that when all of the house pool pending bets are settled there is
-        //no remaining bettor balance in the house pool.
-        // msg!("Transfering locked liquidity to house pool");
-        // let transfer_instruction = transfer(
-        //     &token_program.key,
-        //     &bet_usdt_account.key,
-        //     &hp_usdt_account.key,
-        //     &pda_account.key,
-        //     &[&pda_account.key],
-        //     usd_state.amount
-        // )?;
-        // invoke_signed(
-        //     &transfer_instruction,
-        //     &[
-        //         bet_usdt_account.clone(),
-        //         hp_usdt_account.clone(),
-        //         pda_account.clone(),
-        //         token_program.clone(),
-        //     ],
-        //     &[&[b"divvybetting", &[251]]],
-        // )?;
-        if pool_state.pending_bets == 0 {
-            // msg!("House pool pending bets are settled. Asserting.");
-            // if pool_state.locked_liquidity != 0 {
-            //     return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
-            // }
-            // if pool_state.live_liquidity != 0 {
-            //     return Err(ExchangeError::HousePoolLockedLiquidityRemaining.into());
-            // }
+        // The market cannot settle its PnL while the leg is open
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_add(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        Ok(market_state)
+    }
+
+    /// Takes the risk of a parlay or teaser from the bettor and locks its
+    /// payout from the house pool until it settles.
+    #[allow(clippy::too_many_arguments)]
+    fn fund_parlay<'a>(
+        pool_state: &mut BettingPoolState,
+        risk: u64,
+        payout: u64,
+        bump_seed: u8,
+        initializer: &AccountInfo<'a>,
+        user_usdt_account: &AccountInfo<'a>,
+        bet_usdt_account: &AccountInfo<'a>,
+        hp_usdt_account: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        pda_account: &AccountInfo<'a>,
+        bet_pda_account: &AccountInfo<'a>,
+        pool_state_account: &AccountInfo<'a>,
+        divvy_hp_program: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if payout == 0 {
+            return Err(ExchangeError::PayoutZero.into());
         }
-        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
-        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+        let hp_usdt_state = TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
+        let available_liquidity = calculate_available_liquidity(&hp_usdt_state, pool_state)?;
+        if payout > available_liquidity {
+            return Err(ExchangeError::NotEnoughAvailableLiquidityForBet.into());
+        }
+        // A parlay locks its payout by itself, held to the share of a single market
+        msg!("Checking market liquidity ceiling");
+        let market_liquidity_ceiling = calculate_liquidity_ceiling(
+            available_liquidity,
+            pool_state,
+            pool_state.max_market_liquidity_bps,
+        )?;
+        if payout > market_liquidity_ceiling {
+            return Err(ExchangeError::MarketLiquidityCeilingExceeded.into());
+        }
+
+        msg!("Incrementing house pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_add(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        pool_state.locked_liquidity = pool_state
+            .locked_liquidity
+            .checked_add(payout)
+            .ok_or(ExchangeError::NotEnoughAvailableLiquidityForBet)?;
+
+        //Transfer USDT from user account to bet pool account
+        let transfer_instruction = transfer(
+            token_program.key,
+            user_usdt_account.key,
+            bet_usdt_account.key,
+            initializer.key,
+            &[initializer.key],
+            risk,
+        )?;
+        msg!("Transferring risk from user account to divvy account");
+        invoke(
+            &transfer_instruction,
+            &[
+                user_usdt_account.clone(),
+                bet_usdt_account.clone(),
+                initializer.clone(),
+                token_program.clone(),
+            ],
+        )?;
+        Self::rebalance_locked_liquidity(
+            0,
+            payout,
+            bump_seed,
+            token_program,
+            pda_account,
+            bet_pda_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            pool_state_account,
+            divvy_hp_program,
+        )
+    }
+
+    fn process_init_teaser_config(
+        accounts: &[AccountInfo],
+        teaser_points: i32,
+        odds: &[i64; TeaserConfig::ODDS_LEN],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let teaser_config_account = next_account_info(accounts_iter)?;
+        msg!("Checking if initializer is signer");
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        msg!("Checking if initializer is authorized");
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *teaser_config_account.owner != *program_id {
+            return Err(ExchangeError::InvalidTeaserConfigAccount.into());
+        }
+        if TeaserConfig::unpack_unchecked(&teaser_config_account.data.borrow())?.is_initialized {
+            return Err(ExchangeError::InvalidTeaserConfigAccount.into());
+        }
+        if !Rent::get()?.is_exempt(
+            **teaser_config_account.lamports.borrow(),
+            teaser_config_account.data_len(),
+        ) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let teaser_config = TeaserConfig {
+            is_initialized: true,
+            teaser_points,
+            odds: *odds,
+        };
+        msg!("Checking teaser terms");
+        if !teaser_config.is_valid() {
+            return Err(ExchangeError::InvalidTeaserConfig.into());
+        }
+        TeaserConfig::pack(teaser_config, &mut teaser_config_account.data.borrow_mut())?;
         Ok(())
     }
 
-    fn process_settle_bet(
+    fn process_settle_parlay(
         accounts: &[AccountInfo],
-        bump_seed: u8,
+        _bump_seed: u8,
         program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let _initializer = next_account_info(accounts_iter)?;
         let token_program = next_account_info(accounts_iter)?;
-        let market_state_account = next_account_info(accounts_iter)?;
-        let bet_state_account = next_account_info(accounts_iter)?;
+        let parlay_account = next_account_info(accounts_iter)?;
         let pda_account = next_account_info(accounts_iter)?;
         let bet_usdt_account = next_account_info(accounts_iter)?;
         let user_usdt_account = next_account_info(accounts_iter)?;
@@ -605,15 +4012,11 @@ impl Processor {
         let hp_usdt_account = next_account_info(accounts_iter)?;
         let insurance_fund_usdt_account = next_account_info(accounts_iter)?;
         let divvy_foundation_proceeds_usdt = next_account_info(accounts_iter)?;
-        // Unpack token accounts to verify their length
-        msg!("Check token account accounts length");
-        TokenAccount::unpack(&hp_usdt_account.data.borrow())?;
-        TokenAccount::unpack(&bet_usdt_account.data.borrow())?;
-        TokenAccount::unpack(&insurance_fund_usdt_account.data.borrow())?;
-        TokenAccount::unpack(&divvy_foundation_proceeds_usdt.data.borrow())?;
         let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
-        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
-        let mut bet_state = Bet::unpack(&bet_state_account.data.borrow())?;
+        if *parlay_account.owner != *program_id {
+            return Err(ExchangeError::InvalidParlayAccount.into());
+        }
+        let mut parlay = Parlay::unpack(&parlay_account.data.borrow())?;
 
         if *insurance_fund_usdt_account.key != pool_state.insurance_fund_usdt {
             return Err(ExchangeError::InvalidInsuranceFundUsdtAccount.into());
@@ -621,105 +4024,105 @@ impl Processor {
         if *divvy_foundation_proceeds_usdt.key != pool_state.divvy_foundation_proceeds_usdt {
             return Err(ExchangeError::InvalidDivvyFoundationUsdtAccount.into());
         }
-        if *token_program.key != token_program_id::ID {
-            return Err(ExchangeError::InvalidInstruction.into());
-        }
-        if *market_state_account.owner != *program_id {
-            return Err(ExchangeError::InvalidMarketAccount.into());
+        if *hp_usdt_account.key != pool_state.house_pool_usdt {
+            return Err(ExchangeError::InvalidHousePoolUsdtAccount.into());
         }
-        // Checking bet pool usdt account
         if *bet_usdt_account.key != pool_state.betting_pool_usdt {
             return Err(ExchangeError::InvalidBettingPoolUsdtAccount.into());
         }
-
-        if *market_state_account.owner != *program_id {
-            return Err(ExchangeError::InvalidMarketAccount.into());
-        }
         if *token_program.key != token_program_id::ID {
             return Err(ExchangeError::InvalidInstruction.into());
         }
-
-        if bet_state.market != *market_state_account.key {
+        if parlay.user_usdt_account != *user_usdt_account.key
+            || parlay.user_main_account != *user_main_account.key
+        {
             return Err(ExchangeError::ExpectedDataMismatch.into());
         }
-
         //Checking if betting is frozen
         if pool_state.frozen_betting {
             return Err(ExchangeError::BettingFrozen.into());
         }
-        if market_state.result == MarketOutcome::NotYetCommenced {
-            return Err(ExchangeError::MarketNotSettled.into());
+        if parlay.outcome != 0 {
+            return Err(ExchangeError::BetAlreadySettled.into());
         }
+        let teaser_config = match parlay.teaser_config {
+            Some(teaser_config_key) => {
+                let teaser_config_account = next_account_info(accounts_iter)?;
+                if *teaser_config_account.key != teaser_config_key
+                    || *teaser_config_account.owner != *program_id
+                {
+                    return Err(ExchangeError::InvalidTeaserConfigAccount.into());
+                }
+                Some(TeaserConfig::unpack(&teaser_config_account.data.borrow())?)
+            }
+            None => None,
+        };
 
-        if bet_state.user_usdt_account != *user_usdt_account.key {
-            return Err(ExchangeError::ExpectedDataMismatch.into());
+        msg!("Settling parlay legs");
+        let slot = Clock::get()?.slot;
+        let mut leg_market_accounts = Vec::with_capacity(parlay.legs.len());
+        for leg in parlay.legs.iter_mut() {
+            let market_state_account = next_account_info(accounts_iter)?;
+            if *market_state_account.key != leg.market || *market_state_account.owner != *program_id {
+                return Err(ExchangeError::ExpectedDataMismatch.into());
+            }
+            leg_market_accounts.push(market_state_account);
+            if leg.outcome.is_some() {
+                continue;
+            }
+            let market_state = Market::unpack(&market_state_account.data.borrow())?;
+            leg.outcome = if market_state.result == MarketOutcome::Voided {
+                Some(BetOutcome::Void)
+            } else if slot >= market_state.dispute_end_slot {
+                MarketResult::from_market(&market_state)
+                    .map(|result| settle(leg.bet_type, leg.market_side, leg.points, &result))
+            } else {
+                None
+            };
+            if leg.outcome.is_some() {
+                Self::release_parlay_leg(market_state_account)?;
+            }
         }
 
-        if bet_state.user_main_account != *user_main_account.key {
-            return Err(ExchangeError::ExpectedDataMismatch.into());
+        let lost = parlay.legs.iter().any(|leg| leg.outcome == Some(BetOutcome::Loss));
+        if !lost && parlay.legs.iter().any(|leg| leg.outcome.is_none()) {
+            msg!("Parlay legs are waiting for results");
+            parlay.pack(&mut parlay_account.data.borrow_mut())?;
+            return Ok(());
         }
-
-        if bet_state.outcome != 0 {
-            return Err(ExchangeError::BetAlreadySettled.into());
+        // A lost parlay no longer waits on its open legs
+        for (leg, market_state_account) in parlay.legs.iter().zip(leg_market_accounts.iter()) {
+            if leg.outcome.is_none() {
+                Self::release_parlay_leg(market_state_account)?;
+            }
         }
 
-        // Decrement pending bets
-        msg!("Decrementing market pending bets.");
-        market_state.pending_bets = market_state
-            .pending_bets
-            .checked_sub(1)
-            .ok_or(ExchangeError::AmountOverflow)?;
-
-        msg!("Decrementing betting pool pending bets.");
-        msg!(" {} ",pool_state.pending_bets);
-        pool_state.pending_bets = pool_state
-            .pending_bets
-            .checked_sub(1)
-            .ok_or(ExchangeError::AmountOverflow)?;
-        
-        let outcome = get_bet_outcome(&bet_state, &market_state);
-        bet_state.outcome = outcome;
-        if outcome == 1 {
-            // User won
-            let bet_balance = bet_state
-                .user_risk
-                .checked_add(bet_state.user_payout)
-                .ok_or(ExchangeError::AmountOverflow)?;
-
-            // Subtract bettor balance in the market and house pool
-            // Only for winning bets, as when the market settles,
-            // the balance is changed to only include the winning sides risk and payout
-            market_state.bettor_balance = market_state
-                .bettor_balance
-                .checked_sub(bet_balance)
-                .ok_or(ExchangeError::AmountOverflow)?;
-
-            //Remove risk & payout in market side. Only for winning bets, as locked
-            // liquidity was already calculated for losers.
-            if bet_state.bet_type == BetType::MoneyLine {
-                let current_market_side_risk =
-                market_state.market_sides[bet_state.user_market_side as usize].risk;
-                let current_market_side_payout =
-                    market_state.market_sides[bet_state.user_market_side as usize].payout;
-                market_state.market_sides[bet_state.user_market_side as usize].risk =
-                    current_market_side_risk
-                        .checked_sub(bet_state.user_risk)
-                        .ok_or(ExchangeError::MarketSideRiskUnderflow)?;
-                market_state.market_sides[bet_state.user_market_side as usize].payout =
-                    current_market_side_payout
-                        .checked_sub(bet_state.user_payout)
-                        .ok_or(ExchangeError::MarketSidePayoutUnderflow)?;
-            }
+        let bettor_amount = match teaser_config {
+            _ if lost => Some(0),
+            Some(teaser_config) => teaser_bettor_amount(parlay.user_risk, &parlay.legs, &teaser_config),
+            None => parlay_bettor_amount(parlay.user_risk, &parlay.legs),
+        }
+        .ok_or(ExchangeError::AmountOverflow)?;
+        parlay.outcome = match bettor_amount.cmp(&parlay.user_risk) {
+            Ordering::Greater => BetOutcome::Win,
+            Ordering::Equal => BetOutcome::Push,
+            Ordering::Less if bettor_amount == 0 => BetOutcome::Loss,
+            Ordering::Less => BetOutcome::HalfLoss,
+        }
+        .pack();
+        msg!("- Parlay bettor amount");
+        msg!(0, 0, 0, 0, bettor_amount);
 
+        if bettor_amount > 0 {
             let transfer_instruction = transfer(
-                &token_program.key,
-                &bet_usdt_account.key,
-                &user_usdt_account.key,
-                &pda_account.key,
-                &[&pda_account.key],
-                bet_balance,
+                token_program.key,
+                bet_usdt_account.key,
+                user_usdt_account.key,
+                pda_account.key,
+                &[pda_account.key],
+                bettor_amount,
             )?;
-            msg!("Calling the token program to transfer winnings to user.");
+            msg!("Calling the token program to transfer the parlay balance to user.");
             invoke_signed(
                 &transfer_instruction,
                 &[
@@ -728,38 +4131,65 @@ impl Processor {
                     pda_account.clone(),
                     token_program.clone(),
                 ],
-                //To Do Please test bump seed thing
                 &[&[b"divvybetting", &[251]]],
             )?;
         }
-        if outcome == 3 {
-            // Push
-        }
-        //Return rent to the user that placed the bet
-        let balance = bet_state_account.lamports();
-        **bet_state_account.try_borrow_mut_lamports()? -= balance;
+        // The risk and locked payout the bettor did not take go back to the house
+        let remaining_balance = parlay
+            .user_risk
+            .checked_add(parlay.user_payout)
+            .and_then(|balance| balance.checked_sub(bettor_amount))
+            .ok_or(ExchangeError::AmountOverflow)?;
+        Self::distribute_settled_balance(
+            remaining_balance,
+            parlay.user_payout,
+            token_program,
+            pda_account,
+            bet_usdt_account,
+            hp_usdt_account,
+            insurance_fund_usdt_account,
+            divvy_foundation_proceeds_usdt,
+        )?;
+        pool_state.locked_liquidity = pool_state
+            .locked_liquidity
+            .checked_sub(parlay.user_payout)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("Decrementing betting pool pending bets.");
+        pool_state.pending_bets = pool_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
+
+        //Return rent to the user that placed the parlay
+        let balance = parlay_account.lamports();
+        **parlay_account.try_borrow_mut_lamports()? -= balance;
         **user_main_account.try_borrow_mut_lamports()? += balance;
+        parlay.pack(&mut parlay_account.data.borrow_mut())?;
         BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+        Ok(())
+    }
+
+    /// Stops a market waiting on a parlay leg that has settled.
+    fn release_parlay_leg(market_state_account: &AccountInfo) -> ProgramResult {
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
+        market_state.pending_bets = market_state
+            .pending_bets
+            .checked_sub(1)
+            .ok_or(ExchangeError::AmountOverflow)?;
         Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
-        Bet::pack(bet_state, &mut bet_state_account.data.borrow_mut())?;
         Ok(())
     }
 
-    fn process_init_market(
-        accounts: &[AccountInfo],
-        bump_seed: u8,
-        _program_id: &Pubkey,
+    fn process_configure_manual_settlement(
+        accounts: &[AccountInfo],
+        grace_period: i64,
+        _bump_seed: u8,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let initializer = next_account_info(accounts_iter)?;
         let market_state_account = next_account_info(accounts_iter)?;
-        let result_feed_account = next_account_info(accounts_iter)?;
-        let bet_pool_state_account = next_account_info(accounts_iter)?;
-        let market_side_0_odds_feed_account = next_account_info(accounts_iter)?;
-        let market_side_1_odds_feed_account = next_account_info(accounts_iter)?;
-        let market_side_2_odds_feed_account = next_account_info(accounts_iter)?;
-        let market_side_0_points_feed_account = next_account_info(accounts_iter)?;
-        let market_side_1_points_feed_account = next_account_info(accounts_iter)?;
+        let manual_settler_account = next_account_info(accounts_iter)?;
         msg!("Checking if initializer is signer");
         if !initializer.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
@@ -768,146 +4198,87 @@ impl Processor {
         if initializer.key != &authority::ID {
             return Err(ExchangeError::NotValidAuthority.into());
         }
-        msg!("Unpack pool state");
-        let pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
-        msg!("Unpack market state");
-        let mut market_state = Market::unpack_unchecked(&market_state_account.data.borrow())?;
-
-        //Checking if betting is frozen
-        if pool_state.frozen_betting {
-            return Err(ExchangeError::BettingFrozen.into());
-        }
-        if market_state.is_initialized {
-            return Err(ExchangeError::MarketAlreadyInitialized.into());
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
         }
-        if !Rent::get()?.is_exempt(
-            **market_state_account.lamports.borrow(),
-            market_state_account.data_len(),
-        ) {
-            return Err(ProgramError::AccountNotRentExempt);
+        if grace_period < 0 {
+            return Err(ExchangeError::InvalidInstruction.into());
         }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
 
-        let market_sides: [MarketSide; 3] = 
-                [
-                    MarketSide {
-                        odds_feed_account: Some(*market_side_0_odds_feed_account.key),
-                        points_feed_account: Some(*market_side_0_points_feed_account.key),
-                        payout: 0,
-                        risk: 0,
-                    },
-                    MarketSide {
-                        odds_feed_account: Some(*market_side_1_odds_feed_account.key),
-                        points_feed_account: Some(*market_side_1_points_feed_account.key),
-                        payout: 0,
-                        risk: 0,
-                    },
-                    MarketSide {
-                        odds_feed_account: Some(*market_side_2_odds_feed_account.key),
-                        points_feed_account: None,
-                        payout: 0,
-                        risk: 0,
-                    },
-                ];
-
-        market_state = Market {
-            is_initialized: true,
-            market_sides: market_sides,
-            locked_liquidity: 0,
-            result_feed: *result_feed_account.key,
-            result: MarketOutcome::NotYetCommenced,
-            bettor_balance: 0,
-            pending_bets: 0,
-            team_a_score: 0,
-            team_b_score: 0,
-            total_score: 0
-        };
+        market_state.manual_settler = *manual_settler_account.key;
+        market_state.manual_settlement_grace_period = grace_period;
         Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
-
         Ok(())
     }
 
-    fn process_init_futures_market(
+    #[allow(clippy::too_many_arguments)]
+    fn process_manual_settle_market(
         accounts: &[AccountInfo],
-        bump_seed: u8,
-        _program_id: &Pubkey,
+        winning_side: u8,
+        team_a_score: u16,
+        team_b_score: u16,
+        _bump_seed: u8,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let initializer = next_account_info(accounts_iter)?;
         let market_state_account = next_account_info(accounts_iter)?;
-        let result_feed_account = next_account_info(accounts_iter)?;
         let bet_pool_state_account = next_account_info(accounts_iter)?;
-        let market_side_0_odds_feed_account = next_account_info(accounts_iter)?;
-        let market_side_1_odds_feed_account = next_account_info(accounts_iter)?;
-        let market_side_2_odds_feed_account = next_account_info(accounts_iter)?;
-        let market_side_0_points_feed_account = next_account_info(accounts_iter)?;
-        let market_side_1_points_feed_account = next_account_info(accounts_iter)?;
         msg!("Checking if initializer is signer");
         if !initializer.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
-        msg!("Checking if initializer is authorized");
-        if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
         }
-        msg!("Unpack pool state");
+        if *bet_pool_state_account.owner != *program_id {
+            return Err(ExchangeError::BettingPoolStateNotInitialized.into());
+        }
+        let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
         let pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
-        msg!("Unpack market state");
-        let mut market_state = Market::unpack_unchecked(&market_state_account.data.borrow())?;
 
-        //Checking if betting is frozen
+        msg!("Checking if initializer is the market settler");
+        if *initializer.key != market_state.manual_settler {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
         if pool_state.frozen_betting {
             return Err(ExchangeError::BettingFrozen.into());
         }
-        if market_state.is_initialized {
-            return Err(ExchangeError::MarketAlreadyInitialized.into());
+        if market_state.result != MarketOutcome::Commenced {
+            return Err(ExchangeError::InvalidMarketStatus.into());
         }
-        if !Rent::get()?.is_exempt(
-            **market_state_account.lamports.borrow(),
-            market_state_account.data_len(),
-        ) {
-            return Err(ProgramError::AccountNotRentExempt);
+        msg!("Checking manual settlement grace period");
+        let earliest_manual_settlement = market_state
+            .commenced_timestamp
+            .checked_add(market_state.manual_settlement_grace_period)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        if Clock::get()?.unix_timestamp < earliest_manual_settlement {
+            return Err(ExchangeError::ManualSettlementTooEarly.into());
+        }
+        if winning_side >= market_state.side_count {
+            return Err(ExchangeError::NotValidMarketResult.into());
         }
 
-        let market_sides: [MarketSide; 3] = 
-                [
-                    MarketSide {
-                        odds_feed_account: Some(*market_side_0_odds_feed_account.key),
-                        points_feed_account: Some(*market_side_0_points_feed_account.key),
-                        payout: 0,
-                        risk: 0,
-                    },
-                    MarketSide {
-                        odds_feed_account: Some(*market_side_1_odds_feed_account.key),
-                        points_feed_account: Some(*market_side_1_points_feed_account.key),
-                        payout: 0,
-                        risk: 0,
-                    },
-                    MarketSide {
-                        odds_feed_account: Some(*market_side_2_odds_feed_account.key),
-                        points_feed_account: None,
-                        payout: 0,
-                        risk: 0,
-                    },
-                ];
+        let result = MarketOutcome::side_won(winning_side).ok_or(ExchangeError::NotValidMarketResult)?;
+        market_state.result.transition(result)?;
+        market_state.team_a_score = team_a_score;
+        market_state.team_b_score = team_b_score;
+        market_state.total_score = team_a_score
+            .checked_add(team_b_score)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        // Manual results go through the same dispute window as oracle results
+        market_state.dispute_end_slot = Clock::get()?
+            .slot
+            .checked_add(DISPUTE_WINDOW_SLOTS)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("Divvy - Market manually resolved");
+        msg!(0, 0, winning_side, team_a_score, team_b_score);
 
-        market_state = Market {
-            is_initialized: true,
-            market_sides: market_sides,
-            locked_liquidity: 0,
-            result_feed: *result_feed_account.key,
-            result: MarketOutcome::NotYetCommenced,
-            bettor_balance: 0,
-            pending_bets: 0,
-            team_a_score: 0,
-            team_b_score: 0,
-            total_score: 0
-        };
         Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
-
         Ok(())
     }
 
-
     fn process_settle_market(
         accounts: &[AccountInfo],
         bump_seed: u8,
@@ -938,68 +4309,61 @@ impl Processor {
         if result_account.key != &market_state.result_feed {
             return Err(ExchangeError::NotValidAuthority.into());
         }
-        //Checking if market is not settled yet
-        //TODO check if market is not commenced  and issue a different warning
-        if market_state.result != MarketOutcome::Commenced
-        {
-            return Err(ExchangeError::MarketAlreadySettled.into());
+        //Checking if market is live and awaiting its result
+        if market_state.result != MarketOutcome::Commenced {
+            return Err(ExchangeError::InvalidMarketStatus.into());
         }
-        //Getting results from Switchboard
-        msg!("Unpacking switchboard aggregator.");
-        let aggregator: AggregatorState = get_aggregator(result_account)?;
-        msg!("Unpacking switchboard result.");
-        let round_result: RoundResult = get_aggregator_result(&aggregator)?;
-        msg!("Unpacking switchboard result option.");
-        let result_u8 = round_result
-            .result
-            .ok_or(ExchangeError::FeedNotInitialized)? as u8;
-        msg!("- Result feed");
-        msg!(0, 0, 0, 0, result_u8);
-        if result_u8 > 2 {
-            return Err(ExchangeError::NotValidMarketResult.into());
+        //Getting results from Switchboard, primary oracle first
+        let oracles = market_state.result_oracles();
+        let mut reports = Vec::with_capacity(oracles.len());
+        reports.push(Self::read_result_oracle(
+            &oracles[0],
+            &market_state.feed_checks,
+            market_state.side_count,
+            result_account,
+            teama_points_account,
+            teamb_points_account,
+        )?);
+        for oracle in oracles[1..].iter() {
+            reports.push(Self::read_result_oracle(
+                oracle,
+                &market_state.feed_checks,
+                market_state.side_count,
+                next_account_info(accounts_iter)?,
+                next_account_info(accounts_iter)?,
+                next_account_info(accounts_iter)?,
+            )?);
         }
 
-        //Getting team A score from Switchboard
-        msg!("Unpacking switchboard aggregator.");
-        let aggregator: AggregatorState = get_aggregator(teama_points_account)?;
-        msg!("Unpacking switchboard result.");
-        let round_result: RoundResult = get_aggregator_result(&aggregator)?;
-        msg!("Unpacking switchboard result option.");
-        let teama_u16 = round_result
-            .result
-            .ok_or(ExchangeError::FeedNotInitialized)? as u16;
-        msg!("Team A score: {}", teama_u16);
-
-                
-        //Getting team B score from Switchboard
-        msg!("Unpacking switchboard aggregator.");
-        let aggregator: AggregatorState = get_aggregator(teamb_points_account)?;
-        msg!("Unpacking switchboard result.");
-        let round_result: RoundResult = get_aggregator_result(&aggregator)?;
-        msg!("Unpacking switchboard result option.");
-        let teamb_u16 = round_result
-            .result
-            .ok_or(ExchangeError::FeedNotInitialized)? as u16;
-        msg!("Team B score: {}", teamb_u16);
+        msg!("Checking result quorum");
+        let (result_u8, teama_u16, teamb_u16) = *reports
+            .iter()
+            .find(|report| {
+                reports.iter().filter(|other| other == report).count()
+                    >= market_state.result_quorum as usize
+            })
+            .ok_or(ExchangeError::OracleQuorumNotReached)?;
+        msg!("- Result feed");
+        msg!(0, 0, 0, 0, result_u8);
 
         let total_score = teama_u16 + teamb_u16;
-        let result = match result_u8 {
-            0 => MarketOutcome::MarketSide0Won,
-            1 => MarketOutcome::MarketSide1Won,
-            2 => MarketOutcome::MarketSide2Won,
-            3 => MarketOutcome::NotYetCommenced,
-            4 => MarketOutcome::Commenced,
-            _ => return Err(ExchangeError::NotValidMarketResult.into()),
-        };
+        let result = MarketOutcome::side_won(result_u8).ok_or(ExchangeError::NotValidMarketResult)?;
 
 
         msg!("- Market state");
         msg!(market_state.result.into());
 
-        market_state.result = result;
+        market_state.result.transition(result)?;
         market_state.team_a_score = teama_u16;
         market_state.team_b_score = teamb_u16;
         market_state.total_score = total_score;
+        // Bets can only settle once the result has gone unchallenged for the dispute window
+        market_state.dispute_end_slot = Clock::get()?
+            .slot
+            .checked_add(DISPUTE_WINDOW_SLOTS)
+            .ok_or(ExchangeError::AmountOverflow)?;
+        msg!("- Dispute window ends at slot");
+        msg!(0, 0, 0, 0, market_state.dispute_end_slot);
 
         Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
         BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
@@ -1056,6 +4420,8 @@ impl Processor {
             insurance_fund_usdt: *insurance_fund_usdt_account.key,
             divvy_foundation_proceeds_usdt: *divvy_foundation_proceeds_usdt.key,
             frozen_betting: false,
+            max_market_liquidity_bps: BettingPoolState::NO_LIQUIDITY_CEILING_BPS,
+            max_event_liquidity_bps: BettingPoolState::NO_LIQUIDITY_CEILING_BPS,
         };
         BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
         Ok(())
@@ -1064,7 +4430,7 @@ impl Processor {
     pub fn process_commence_market(
         accounts: &[AccountInfo],
         bump_seed: u8,
-        _program_id: &Pubkey,
+        program_id: &Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let initializer = next_account_info(accounts_iter)?;
@@ -1072,16 +4438,27 @@ impl Processor {
         if !initializer.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
-        msg!("Check authority");
-        if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
-        }
         let market_state_account = next_account_info(accounts_iter)?;
         let bet_pool_state_account = next_account_info(accounts_iter)?;
 
+        if *market_state_account.owner != *program_id {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if *bet_pool_state_account.owner != *program_id {
+            return Err(ExchangeError::BettingPoolStateNotInitialized.into());
+        }
+
         let mut market_state = Market::unpack(&market_state_account.data.borrow())?;
         let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
 
+        // Anyone can crank the market once its start time has passed
+        msg!("Check authority");
+        if Clock::get()?.unix_timestamp < market_state.start_timestamp
+            && initializer.key != &authority::ID
+        {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+
         //Check house pool porgram ID
         // divvy_house_program_id::ID
 
@@ -1090,11 +4467,8 @@ impl Processor {
             return Err(ExchangeError::BettingFrozen.into());
         }
         msg!("Check market commence status");
-        if market_state.result != MarketOutcome::NotYetCommenced {
-            return Err(ExchangeError::MarketCommenced.into());
-        }
-
-        market_state.result = MarketOutcome::Commenced;
+        market_state.result.transition(MarketOutcome::Commenced)?;
+        market_state.commenced_timestamp = Clock::get()?.unix_timestamp;
         pool_state.locked_liquidity = pool_state
             .locked_liquidity
             .checked_sub(market_state.locked_liquidity)
@@ -1143,6 +4517,140 @@ impl Processor {
 
 
 
+    pub fn process_configure_liquidity_ceilings(
+        accounts: &[AccountInfo],
+        max_market_liquidity_bps: u16,
+        max_event_liquidity_bps: u16,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if max_market_liquidity_bps == 0
+            || max_market_liquidity_bps > 10_000
+            || max_event_liquidity_bps < max_market_liquidity_bps
+            || max_event_liquidity_bps > 10_000
+        {
+            return Err(ExchangeError::InvalidInstruction.into());
+        }
+
+        let mut pool_state = BettingPoolState::unpack(&bet_pool_state_account.data.borrow())?;
+        pool_state.max_market_liquidity_bps = max_market_liquidity_bps;
+        pool_state.max_event_liquidity_bps = max_event_liquidity_bps;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_migrate_betting_pool(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let legacy_bet_pool_state_account = next_account_info(accounts_iter)?;
+        let bet_pool_state_account = next_account_info(accounts_iter)?;
+
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *legacy_bet_pool_state_account.owner != *program_id
+            || *bet_pool_state_account.owner != *program_id
+        {
+            return Err(ExchangeError::BettingPoolStateNotInitialized.into());
+        }
+        if legacy_bet_pool_state_account.data_len() != BettingPoolState::LEGACY_LEN
+            || bet_pool_state_account.data_len() != BettingPoolState::LEN
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if BettingPoolState::unpack_unchecked(&bet_pool_state_account.data.borrow())?.is_initialized {
+            return Err(ExchangeError::BettingPoolStateAlreadyInitialized.into());
+        }
+        msg!("Check Rent Exemption");
+        if !Rent::get()?.is_exempt(
+            **bet_pool_state_account.lamports.borrow(),
+            bet_pool_state_account.data_len(),
+        ) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        msg!("Copying betting pool state to the new account");
+        let pool_state = BettingPoolState::unpack(&legacy_bet_pool_state_account.data.borrow())?;
+        BettingPoolState::pack(pool_state, &mut bet_pool_state_account.data.borrow_mut())?;
+
+        // Clear the legacy account so it can't be used as a pool any more
+        legacy_bet_pool_state_account.data.borrow_mut().fill(0);
+        let balance = legacy_bet_pool_state_account.lamports();
+        **legacy_bet_pool_state_account.try_borrow_mut_lamports()? -= balance;
+        **initializer.try_borrow_mut_lamports()? += balance;
+
+        Ok(())
+    }
+
+    pub fn process_migrate_market(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let legacy_market_state_account = next_account_info(accounts_iter)?;
+        let market_state_account = next_account_info(accounts_iter)?;
+
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if initializer.key != &authority::ID {
+            return Err(ExchangeError::NotValidAuthority.into());
+        }
+        if *legacy_market_state_account.owner != *program_id
+            || *market_state_account.owner != *program_id
+        {
+            return Err(ExchangeError::InvalidMarketAccount.into());
+        }
+        if legacy_market_state_account.data_len() != Market::LEGACY_LEN
+            || market_state_account.data_len() != Market::LEN
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if Market::unpack_unchecked(&market_state_account.data.borrow())?.is_initialized {
+            return Err(ExchangeError::MarketAlreadyInitialized.into());
+        }
+        msg!("Check Rent Exemption");
+        if !Rent::get()?.is_exempt(
+            **market_state_account.lamports.borrow(),
+            market_state_account.data_len(),
+        ) {
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let market_state = Market::unpack(&legacy_market_state_account.data.borrow())?;
+        // Bets and user exposures are tied to the legacy address
+        if market_state.pending_bets != 0 {
+            return Err(ExchangeError::MarketBetsPending.into());
+        }
+
+        msg!("Copying market state to the new account");
+        Market::pack(market_state, &mut market_state_account.data.borrow_mut())?;
+
+        // Clear the legacy account so it can't be used as a market any more
+        legacy_market_state_account.data.borrow_mut().fill(0);
+        let balance = legacy_market_state_account.lamports();
+        **legacy_market_state_account.try_borrow_mut_lamports()? -= balance;
+        **initializer.try_borrow_mut_lamports()? += balance;
+
+        Ok(())
+    }
+
     pub fn process_init_new_bust(
         accounts: &[AccountInfo],
         multiplier: u32,
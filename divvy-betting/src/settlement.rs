@@ -0,0 +1,265 @@
+//! Settlement rules deciding how a bet ends once its market has a result.
+//!
+//! Sides are read from the bettor's perspective:
+//! - Moneyline: the side the bettor backs to win, 2 being the draw.
+//! - Spread: side 0 backs team A and side 1 backs team B, with `points` the
+//!   handicap added to the backed team's score, so -350 gives 3.5 points.
+//! - Total: side 0 is the Under and side 1 the Over on a line of `points`.
+//! - Asian handicap: a spread whose quarter lines split the stake over the
+//!   two neighbouring half lines, so -0.25 rides half on 0 and half on -0.5.
+//!
+//! Lines are in hundredths of a point (see `POINTS_SCALE`). Whole lines push
+//! when the result lands on them, half-point lines never do.
+
+use crate::{
+    error::ExchangeError,
+    state::{Bet, BetType, Market, MarketOutcome, ParlayLeg, TeaserConfig},
+    PARLAY_ODDS_SCALE, POINTS_SCALE,
+};
+use solana_program::program_error::ProgramError;
+use std::{cmp::Ordering, convert::TryFrom};
+
+/// How a bet settles. Packed into `Bet::outcome`, where 0 means not settled yet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BetOutcome {
+    /// The bettor gets the risk back along with the payout.
+    Win,
+    /// The risk stays with the market.
+    Loss,
+    /// The result landed exactly on the line and the risk is returned.
+    Push,
+    /// The bet could not be graded and the risk is returned.
+    Void,
+    /// Half the stake won and the other half pushed: the risk comes back
+    /// with half the payout.
+    HalfWin,
+    /// Half the stake lost and the other half pushed: half the risk comes back.
+    HalfLoss,
+}
+
+impl BetOutcome {
+    pub fn pack(&self) -> u8 {
+        match *self {
+            BetOutcome::Win => 1,
+            BetOutcome::Loss => 2,
+            BetOutcome::Push => 3,
+            BetOutcome::Void => 4,
+            BetOutcome::HalfWin => 5,
+            BetOutcome::HalfLoss => 6,
+        }
+    }
+
+    /// Reverse of `pack`, `None` for a bet not settled yet.
+    pub fn unpack(input: u8) -> Result<Option<Self>, ProgramError> {
+        Ok(Some(match input {
+            0 => return Ok(None),
+            1 => BetOutcome::Win,
+            2 => BetOutcome::Loss,
+            3 => BetOutcome::Push,
+            4 => BetOutcome::Void,
+            5 => BetOutcome::HalfWin,
+            6 => BetOutcome::HalfLoss,
+            _ => return Err(ExchangeError::InvalidInstruction.into()),
+        }))
+    }
+
+    /// Whether the bettor only gets the risk back.
+    pub fn is_refund(&self) -> bool {
+        matches!(*self, BetOutcome::Push | BetOutcome::Void)
+    }
+
+    /// What the bettor is paid back on a bet of `risk` that would pay `payout` on a win.
+    pub fn bettor_amount(&self, risk: u64, payout: u64) -> Option<u64> {
+        match *self {
+            BetOutcome::Win => risk.checked_add(payout),
+            BetOutcome::HalfWin => risk.checked_add(payout / 2),
+            BetOutcome::Push | BetOutcome::Void => Some(risk),
+            BetOutcome::HalfLoss => Some(risk / 2),
+            BetOutcome::Loss => Some(0),
+        }
+    }
+}
+
+/// What the bettor of a parlay is paid back once its legs are settled: the
+/// risk carried through the odds of every leg, a void or pushed leg dropping
+/// out and a half-settled leg carrying half the stake through its odds.
+/// Legs not settled yet count as won, so this is the locked amount at placement.
+pub fn parlay_bettor_amount(risk: u64, legs: &[ParlayLeg]) -> Option<u64> {
+    let scale = u128::from(PARLAY_ODDS_SCALE);
+    // Kept scaled so only the final amount is rounded
+    let mut amount = u128::from(risk) * scale;
+    for leg in legs.iter() {
+        let decimal_odds = u128::from(leg.decimal_odds);
+        let factor = match leg.outcome {
+            None | Some(BetOutcome::Win) => decimal_odds,
+            Some(BetOutcome::HalfWin) => (scale + decimal_odds) / 2,
+            Some(BetOutcome::Push) | Some(BetOutcome::Void) => scale,
+            Some(BetOutcome::HalfLoss) => scale / 2,
+            Some(BetOutcome::Loss) => 0,
+        };
+        amount = amount.checked_mul(factor)? / scale;
+    }
+    u64::try_from(amount / scale).ok()
+}
+
+/// What the house pays for a bet of `risk` that would pay `payout` on a win,
+/// sold back at the current `decimal_odds` of its side (scaled by
+/// `PARLAY_ODDS_SCALE`): the win amount weighted by the chance the odds
+/// imply, less `margin_bps` basis points.
+pub fn cash_out_value(risk: u64, payout: u64, decimal_odds: u64, margin_bps: u16) -> Option<u64> {
+    if decimal_odds < PARLAY_ODDS_SCALE || margin_bps > 10_000 {
+        return None;
+    }
+    let win_amount = u128::from(risk.checked_add(payout)?);
+    let fair_value = win_amount * u128::from(PARLAY_ODDS_SCALE) / u128::from(decimal_odds);
+    u64::try_from(fair_value * u128::from(10_000 - margin_bps) / 10_000).ok()
+}
+
+/// What the bettor of a teaser is paid back once its legs are settled. Any
+/// losing leg loses the teaser, void and pushed legs drop out and the rest
+/// are paid at the config's odds for that many legs. The risk is returned
+/// when too few legs are left or that many legs are not offered.
+/// Legs not settled yet count as won, so this is the locked amount at placement.
+pub fn teaser_bettor_amount(risk: u64, legs: &[ParlayLeg], config: &TeaserConfig) -> Option<u64> {
+    let mut winning_legs = 0;
+    for leg in legs.iter() {
+        match leg.outcome {
+            None | Some(BetOutcome::Win) => winning_legs += 1,
+            Some(BetOutcome::Push) | Some(BetOutcome::Void) => {}
+            // Teasers only take spread and total legs, which never settle in halves
+            Some(BetOutcome::HalfWin) | Some(BetOutcome::HalfLoss) | Some(BetOutcome::Loss) => {
+                return Some(0)
+            }
+        }
+    }
+    match config.decimal_odds(winning_legs) {
+        Some(decimal_odds) => u64::try_from(
+            u128::from(risk).checked_mul(u128::from(decimal_odds))? / u128::from(PARLAY_ODDS_SCALE),
+        )
+        .ok(),
+        None => Some(risk),
+    }
+}
+
+/// The line of a teaser leg: `points` moved by `teaser_points` in favour of
+/// the bettor. `None` for bet types that cannot be teased.
+pub fn teased_line(bet_type: BetType, market_side: u8, points: i32, teaser_points: i32) -> Option<i32> {
+    match (bet_type, market_side) {
+        // More points for the backed team
+        (BetType::Spread, 0) | (BetType::Spread, 1) => points.checked_add(teaser_points),
+        // A higher Under or a lower Over
+        (BetType::Total, 0) => points.checked_add(teaser_points),
+        (BetType::Total, 1) => points.checked_sub(teaser_points),
+        _ => None,
+    }
+}
+
+/// Asian handicap lines move in quarter points.
+const QUARTER_POINT: i32 = POINTS_SCALE / 4;
+
+/// Whether `points` is a line a bet of `bet_type` can be placed at.
+pub fn is_valid_line(bet_type: BetType, points: i32) -> bool {
+    match bet_type {
+        BetType::MoneyLine => points == 0,
+        BetType::Spread | BetType::Total => true,
+        BetType::AsianHandicap => points % QUARTER_POINT == 0,
+    }
+}
+
+/// Final result of a market.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MarketResult {
+    pub winning_side: u8,
+    pub team_a_score: u16,
+    pub team_b_score: u16,
+}
+
+impl MarketResult {
+    /// The result recorded on `market`, `None` until `SettleMarket` ran or if it was voided.
+    pub fn from_market(market: &Market) -> Option<MarketResult> {
+        let winning_side = match market.result {
+            MarketOutcome::MarketSide0Won => 0,
+            MarketOutcome::MarketSide1Won => 1,
+            MarketOutcome::MarketSide2Won => 2,
+            _ => return None,
+        };
+        Some(MarketResult {
+            winning_side,
+            team_a_score: market.team_a_score,
+            team_b_score: market.team_b_score,
+        })
+    }
+}
+
+/// Settles `bet` against the result of its market. Bets on a market without
+/// a result are void.
+pub fn bet_outcome(bet: &Bet, market: &Market) -> BetOutcome {
+    match MarketResult::from_market(market) {
+        Some(result) => settle(bet.bet_type, bet.user_market_side, bet.points, &result),
+        None => BetOutcome::Void,
+    }
+}
+
+/// Settles a bet of `bet_type` on `market_side` at a line of `points`.
+pub fn settle(bet_type: BetType, market_side: u8, points: i32, result: &MarketResult) -> BetOutcome {
+    match bet_type {
+        BetType::MoneyLine => moneyline(market_side, result),
+        BetType::Spread => spread(market_side, points, result),
+        BetType::Total => total(market_side, points, result),
+        BetType::AsianHandicap => asian_handicap(market_side, points, result),
+    }
+}
+
+fn moneyline(market_side: u8, result: &MarketResult) -> BetOutcome {
+    if market_side == result.winning_side {
+        BetOutcome::Win
+    } else {
+        BetOutcome::Loss
+    }
+}
+
+fn spread(market_side: u8, points: i32, result: &MarketResult) -> BetOutcome {
+    let team_a_margin = i64::from(result.team_a_score) - i64::from(result.team_b_score);
+    let backed_margin = match market_side {
+        0 => team_a_margin,
+        1 => -team_a_margin,
+        _ => return BetOutcome::Void,
+    };
+    let margin = backed_margin * i64::from(POINTS_SCALE) + i64::from(points);
+    graded(margin.cmp(&0))
+}
+
+fn total(market_side: u8, points: i32, result: &MarketResult) -> BetOutcome {
+    let total_score = i64::from(result.team_a_score) + i64::from(result.team_b_score);
+    let over = (total_score * i64::from(POINTS_SCALE)).cmp(&i64::from(points));
+    match market_side {
+        0 => graded(over.reverse()),
+        1 => graded(over),
+        _ => BetOutcome::Void,
+    }
+}
+
+fn asian_handicap(market_side: u8, points: i32, result: &MarketResult) -> BetOutcome {
+    if points % (2 * QUARTER_POINT) == 0 {
+        return spread(market_side, points, result);
+    }
+    // Quarter line: half the stake on each neighbouring half line
+    let lower = spread(market_side, points.saturating_sub(QUARTER_POINT), result);
+    let upper = spread(market_side, points.saturating_add(QUARTER_POINT), result);
+    match (lower, upper) {
+        (BetOutcome::Push, BetOutcome::Win) => BetOutcome::HalfWin,
+        (BetOutcome::Loss, BetOutcome::Push) => BetOutcome::HalfLoss,
+        (lower, upper) if lower == upper => lower,
+        // A larger handicap never grades worse, so the halves cannot disagree further
+        _ => BetOutcome::Void,
+    }
+}
+
+/// Maps the bettor's margin over the line to an outcome.
+fn graded(margin: Ordering) -> BetOutcome {
+    match margin {
+        Ordering::Greater => BetOutcome::Win,
+        Ordering::Equal => BetOutcome::Push,
+        Ordering::Less => BetOutcome::Loss,
+    }
+}
@@ -1,14 +1,21 @@
-use solana_program::{program_error::ProgramError, program_pack::{IsInitialized, Pack, Sealed}, pubkey::Pubkey};
+use std::convert::TryFrom;
+
+use solana_program::{clock::{Slot, UnixTimestamp}, msg, program_error::ProgramError, program_pack::{IsInitialized, Pack, Sealed}, pubkey::Pubkey};
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 
-use crate::{error::ExchangeError::InvalidInstruction, pack_pubkey_option, unpack_pubkey_option};
+use crate::{calculate_decimal_odds, error::ExchangeError::{self, InvalidInstruction}, pack_pubkey_option, schema::authority, POINTS_SCALE, settlement::BetOutcome, unpack_pubkey_option};
+
+pub use divvycommon::state::{AccountType, BettingPoolState};
 
+#[derive(PartialEq)]
 pub struct Market {
     pub is_initialized: bool,
     pub market_sides: [MarketSide; 3],
     pub locked_liquidity: u64,
     pub result_feed: Pubkey,
+    /// Team A and team B score feeds, read with `result_feed` as the primary oracle.
+    pub score_feed_accounts: [Pubkey; 2],
     pub result: MarketOutcome,
     /// The amount of risk the bettors have entered into the market.
     /// When the market settles, this equals to the winning sides unsettled risk and payout
@@ -17,6 +24,122 @@ pub struct Market {
     pub team_a_score: u16,
     pub team_b_score: u16,
     pub total_score: u16,
+    /// Scheduled start of the event. Betting closes once the cluster clock reaches it.
+    pub start_timestamp: UnixTimestamp,
+    /// Number of sides in play: 2 for events without a draw, 3 otherwise.
+    /// Sides past this count are left empty.
+    pub side_count: u8,
+    pub metadata: MarketMetadata,
+    /// First slot at which the result recorded by `SettleMarket` is final
+    /// and bets can be settled. Until then the authority can override it.
+    pub dispute_end_slot: Slot,
+    /// Independent result oracles on top of the primary one.
+    pub extra_result_oracles: Vec<ResultOracle>,
+    /// Number of oracles that must report the same result and scores.
+    pub result_quorum: u8,
+    pub feed_checks: FeedChecks,
+    /// When `CommenceMarket` ran, zero before that.
+    pub commenced_timestamp: UnixTimestamp,
+    /// Key allowed to post the result by hand if the result feeds go dead.
+    pub manual_settler: Pubkey,
+    /// Seconds after commencement before `manual_settler` may settle the market.
+    pub manual_settlement_grace_period: i64,
+    pub stake_limits: StakeLimits,
+    /// Set by `SuspendMarket`. Blocks new bets on this market only.
+    pub suspended: bool,
+    /// Whether bets are still taken after commencement, priced from
+    /// `live_odds_feed_accounts`.
+    pub live_betting: bool,
+    /// Slots a live bet waits before `AcceptLiveBet` can book it.
+    pub live_acceptance_delay_slots: u64,
+    /// In-play odds feeds, ordered by market side.
+    pub live_odds_feed_accounts: [Option<Pubkey>; 3],
+    /// Whether bettors can sell their pending bets back with `CashOut`.
+    /// In play only when `live_betting` is also on.
+    pub cash_out: bool,
+    /// Margin taken off the fair value of a cashed out bet, in basis points.
+    pub cash_out_margin_bps: u16,
+}
+
+/// Limits on what a single user can bet on a market.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StakeLimits {
+    pub min_stake: u64,
+    pub max_stake: u64,
+    /// Cap on the summed payout of all of a user's bets on the market.
+    pub max_payout_per_user: u64,
+}
+
+/// Running total of a user's bets on one market, at the address given by
+/// `user_exposure_address`.
+pub struct UserExposure {
+    pub is_initialized: bool,
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub risk: u64,
+    pub payout: u64,
+}
+
+/// Liquidity locked by all the markets of one event, at the address given by
+/// `event_group_address`.
+pub struct EventGroup {
+    pub is_initialized: bool,
+    pub event_id: [u8; 32],
+    pub locked_liquidity: u64,
+}
+
+/// Freshness and confidence a Switchboard round needs before a market reads
+/// odds or results from it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeedChecks {
+    /// Oldest round, in slots since it opened, that is still accepted.
+    pub max_feed_age_slots: u64,
+    /// Fewest successful oracle responses a round needs.
+    pub min_oracle_responses: u32,
+    /// Most a bet's line may differ from the side's points
+    /// feed, in hundredths of a point. Zero requires an exact match.
+    pub max_line_deviation: u32,
+}
+
+/// The feeds one oracle provider publishes for a market's result.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResultOracle {
+    pub result_feed: Pubkey,
+    pub team_a_score_feed: Pubkey,
+    pub team_b_score_feed: Pubkey,
+}
+
+/// Identifies the real world fixture behind a market. Text fields are UTF-8,
+/// zero padded to their fixed size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MarketMetadata {
+    /// Id of the event at the data provider.
+    pub event_id: [u8; 32],
+    /// Sport or league code, e.g. `NBA`.
+    pub league: [u8; 8],
+    /// Participant names ordered like the market sides 0 and 1.
+    pub participants: [[u8; 32]; 2],
+}
+
+/// Outright market with a variable number of sides, e.g. a league winner.
+/// Each side is bound to its own odds feed and the result feed reports the
+/// index of the winning side.
+pub struct FuturesMarket {
+    pub is_initialized: bool,
+    pub market_sides: Vec<MarketSide>,
+    pub locked_liquidity: u64,
+    pub result_feed: Pubkey,
+    pub result: FuturesOutcome,
+    pub bettor_balance: u64,
+    pub pending_bets: u64,
+    pub start_timestamp: UnixTimestamp,
+    pub feed_checks: FeedChecks,
+    pub stake_limits: StakeLimits,
+    /// Set by `SuspendMarket`. Blocks new bets on this market only.
+    pub suspended: bool,
+    /// First slot at which the result recorded by `SettleFuturesMarket` is
+    /// final. Until then the authority can override it.
+    pub dispute_end_slot: Slot,
 }
 
 pub struct SolBust {
@@ -43,31 +166,22 @@ pub struct BustBet {
     pub actual_multiplier_pubkey: Pubkey
 }
 
-
+#[derive(Clone, Copy, PartialEq)]
 pub struct MarketSide {
     pub odds_feed_account: Option<Pubkey>,
+    /// Feed reporting this side's spread or total line, see `points_from_feed`.
     pub points_feed_account: Option<Pubkey>,
     pub payout: u64,
     pub risk: u64,
 }
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub enum BetType {
     MoneyLine,
     Spread,
     Total,
-}
-
-pub struct BettingPoolState {
-    pub is_initialized: bool,
-    pub locked_liquidity: u64,
-    pub live_liquidity: u64,
-    pub pending_bets: u64,
-    pub house_pool_usdt: Pubkey,
-    pub betting_pool_usdt: Pubkey,
-    pub insurance_fund_usdt: Pubkey,
-    pub divvy_foundation_proceeds_usdt: Pubkey,
-    pub frozen_betting: bool,
+    /// Spread on quarter lines, see `settlement`.
+    AsianHandicap,
 }
 
 pub struct Bet {
@@ -77,10 +191,61 @@ pub struct Bet {
     pub user_main_account: Pubkey,
     pub user_risk: u64,
     pub user_payout: u64,
-    pub points: u16,
+    /// Line of the bet in hundredths of a point, zero on moneyline bets.
+    pub points: i32,
     pub user_market_side: u8,
+    /// 0 until settled, then a packed `BetOutcome`, or `Bet::CASHED_OUT`
+    /// once sold back to the house.
+    pub outcome: u8,
+    pub bet_type: BetType,
+    /// Set on live bets until `AcceptLiveBet` books or refunds them. The risk
+    /// is held by the betting pool but the market sides do not include it yet.
+    pub pending_acceptance: bool,
+    /// First slot at which a live bet can be accepted.
+    pub accept_slot: Slot,
+}
+
+/// A bet on two or more markets at once. Its account is sized by
+/// `Parlay::get_packed_len` for the number of legs.
+pub struct Parlay {
+    pub is_initialized: bool,
+    pub user_usdt_account: Pubkey,
+    pub user_main_account: Pubkey,
+    pub user_risk: u64,
+    /// Paid on top of the risk if every leg wins. Locked from the house pool
+    /// until the parlay settles.
+    pub user_payout: u64,
+    /// 0 until settled, then a packed `BetOutcome`: `Win` or `HalfLoss` when
+    /// the bettor got back more or less than the risk, `Push` when exactly
+    /// the risk and `Loss` when nothing.
     pub outcome: u8,
-    pub bet_type: BetType
+    /// Set for a teaser, whose legs hold the teased lines and are paid from
+    /// this config's odds table rather than their own odds.
+    pub teaser_config: Option<Pubkey>,
+    pub legs: Vec<ParlayLeg>,
+}
+
+pub struct ParlayLeg {
+    pub market: Pubkey,
+    pub market_side: u8,
+    pub bet_type: BetType,
+    pub points: i32,
+    /// Decimal odds of the leg when the parlay was placed, scaled by
+    /// `PARLAY_ODDS_SCALE`. 0 for teaser legs.
+    pub decimal_odds: u64,
+    /// `None` until the leg's market has a result.
+    pub outcome: Option<BetOutcome>,
+}
+
+/// Terms of a teaser. A teaser moves the line of every leg by `teaser_points`
+/// in the bettor's favour and pays from a fixed odds table by leg count.
+pub struct TeaserConfig {
+    pub is_initialized: bool,
+    /// In hundredths of a point.
+    pub teaser_points: i32,
+    /// American odds for 2 up to `Parlay::MAX_LEGS` legs, 0 where that many
+    /// legs are not offered.
+    pub odds: [i64; TeaserConfig::ODDS_LEN],
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -91,6 +256,8 @@ pub enum MarketOutcome {
     NotYetCommenced,
     Commenced,
     Settled,
+    /// The event was postponed or abandoned and every bet is refunded.
+    Voided,
 }
 
 impl MarketOutcome {
@@ -101,10 +268,52 @@ impl MarketOutcome {
             2 => Self::MarketSide2Won,
             3 => Self::NotYetCommenced,
             4 => Self::Commenced,
-            _ => return Err(InvalidInstruction.into()),
+            5 => Self::Settled,
+            6 => Self::Voided,
+            _ => return Err(ProgramError::InvalidAccountData),
         })
     }
 
+    /// The outcome for a win of `side`, as reported by the result feed.
+    pub fn side_won(side: u8) -> Option<MarketOutcome> {
+        match side {
+            0 => Some(MarketOutcome::MarketSide0Won),
+            1 => Some(MarketOutcome::MarketSide1Won),
+            2 => Some(MarketOutcome::MarketSide2Won),
+            _ => None,
+        }
+    }
+
+    /// Whether a market in this status may move to `next`.
+    ///
+    /// NotYetCommenced -> Commenced -> MarketSideXWon -> Settled, and a market
+    /// can be voided until a result is in.
+    pub fn can_transition_to(&self, next: MarketOutcome) -> bool {
+        matches!(
+            (*self, next),
+            (MarketOutcome::NotYetCommenced, MarketOutcome::Commenced)
+                | (MarketOutcome::NotYetCommenced, MarketOutcome::Voided)
+                | (MarketOutcome::Commenced, MarketOutcome::MarketSide0Won)
+                | (MarketOutcome::Commenced, MarketOutcome::MarketSide1Won)
+                | (MarketOutcome::Commenced, MarketOutcome::MarketSide2Won)
+                | (MarketOutcome::Commenced, MarketOutcome::Voided)
+                | (MarketOutcome::MarketSide0Won, MarketOutcome::Settled)
+                | (MarketOutcome::MarketSide1Won, MarketOutcome::Settled)
+                | (MarketOutcome::MarketSide2Won, MarketOutcome::Settled)
+        )
+    }
+
+    /// Moves the market to `next`, failing with `InvalidMarketStatus` if the
+    /// transition is not allowed from the current status.
+    pub fn transition(&mut self, next: MarketOutcome) -> Result<(), ExchangeError> {
+        if !self.can_transition_to(next) {
+            msg!("Market can not move from {} to {}", <&str>::from(*self), <&str>::from(next));
+            return Err(ExchangeError::InvalidMarketStatus);
+        }
+        *self = next;
+        Ok(())
+    }
+
     pub fn pack(&self) -> u8 {
         match *self {
             MarketOutcome::MarketSide0Won => 0,
@@ -113,9 +322,65 @@ impl MarketOutcome {
             MarketOutcome::NotYetCommenced => 3,
             MarketOutcome::Commenced => 4,
             MarketOutcome::Settled => 5,
+            MarketOutcome::Voided => 6,
+        }
+    }
+}
+#[derive(PartialEq, Clone, Copy)]
+pub enum FuturesOutcome {
+    NotYetCommenced,
+    Commenced,
+    SideWon(u8),
+    Settled,
+    Voided,
+}
+
+impl FuturesOutcome {
+    pub fn unpack(input: &[u8; 2]) -> Result<Self, ProgramError> {
+        Ok(match input {
+            [0, _] => Self::NotYetCommenced,
+            [1, _] => Self::Commenced,
+            [2, side] => Self::SideWon(*side),
+            [3, _] => Self::Settled,
+            [4, _] => Self::Voided,
+            _ => return Err(ProgramError::InvalidAccountData),
+        })
+    }
+
+    /// Whether a futures market in this status may move to `next`. Like
+    /// moneyline markets, a futures market can be voided until a result is in.
+    pub fn can_transition_to(&self, next: FuturesOutcome) -> bool {
+        matches!(
+            (*self, next),
+            (FuturesOutcome::NotYetCommenced, FuturesOutcome::Commenced)
+                | (FuturesOutcome::NotYetCommenced, FuturesOutcome::Voided)
+                | (FuturesOutcome::Commenced, FuturesOutcome::SideWon(_))
+                | (FuturesOutcome::Commenced, FuturesOutcome::Voided)
+                | (FuturesOutcome::SideWon(_), FuturesOutcome::Settled)
+        )
+    }
+
+    /// Moves the market to `next`, failing with `InvalidMarketStatus` if the
+    /// transition is not allowed from the current status.
+    pub fn transition(&mut self, next: FuturesOutcome) -> Result<(), ExchangeError> {
+        if !self.can_transition_to(next) {
+            return Err(ExchangeError::InvalidMarketStatus);
+        }
+        *self = next;
+        Ok(())
+    }
+
+    pub fn pack(&self) -> [u8; 2] {
+        match *self {
+            FuturesOutcome::NotYetCommenced => [0, 0],
+            FuturesOutcome::Commenced => [1, 0],
+            FuturesOutcome::SideWon(side) => [2, side],
+            FuturesOutcome::Settled => [3, 0],
+            FuturesOutcome::Voided => [4, 0],
         }
     }
 }
+
 impl From<MarketOutcome> for &str {
     fn from(val: MarketOutcome) -> Self {
         match val {
@@ -125,6 +390,7 @@ impl From<MarketOutcome> for &str {
             MarketOutcome::NotYetCommenced => "Not yet commenced",
             MarketOutcome::Commenced => "Commenced",
             MarketOutcome::Settled => "Settled",
+            MarketOutcome::Voided => "Voided",
         }
     }
 }
@@ -212,6 +478,7 @@ impl BetType {
             0 => Self::MoneyLine,
             1 => Self::Spread,
             2 => Self::Total,
+            3 => Self::AsianHandicap,
             _ => return Err(InvalidInstruction.into()),
         })
     }
@@ -221,6 +488,7 @@ impl BetType {
             BetType::MoneyLine => 0,
             BetType::Spread => 1,
             BetType::Total => 2,
+            BetType::AsianHandicap => 3,
         }
     }
 }
@@ -231,47 +499,252 @@ impl From<BetType> for &str {
             BetType::MoneyLine => "Money Line 3 Way",
             BetType::Spread => "Points Spread",
             BetType::Total => "Total Score",
+            BetType::AsianHandicap => "Asian Handicap",
         }
     }
 }
 
-impl Sealed for Market {}
-
-impl Sealed for BettingPoolState {}
+impl Sealed for SolBust {}
 
-impl Sealed for Bet {}
+impl Sealed for UserExposure {}
 
-impl Sealed for SolBust {}
+impl Sealed for EventGroup {}
 
-impl IsInitialized for Market {
+impl IsInitialized for UserExposure {
     fn is_initialized(&self) -> bool {
         self.is_initialized
     }
 }
 
-impl IsInitialized for BettingPoolState {
+impl IsInitialized for EventGroup {
     fn is_initialized(&self) -> bool {
         self.is_initialized
     }
 }
 
-impl IsInitialized for Bet {
+impl IsInitialized for SolBust {
     fn is_initialized(&self) -> bool {
         self.is_initialized
     }
 }
 
-impl IsInitialized for SolBust {
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
+
+impl MarketMetadata {
+    pub const LEN: usize = 104;
+
+    /// Builds the metadata block, failing if a field does not fit.
+    pub fn new(event_id: &str, league: &str, participants: [&str; 2]) -> Result<Self, ProgramError> {
+        let mut metadata = MarketMetadata {
+            event_id: [0; 32],
+            league: [0; 8],
+            participants: [[0; 32]; 2],
+        };
+        pack_str(event_id, &mut metadata.event_id)?;
+        pack_str(league, &mut metadata.league)?;
+        pack_str(participants[0], &mut metadata.participants[0])?;
+        pack_str(participants[1], &mut metadata.participants[1])?;
+        Ok(metadata)
+    }
+
+    /// Checks that every field is valid UTF-8 and that the event id is set.
+    pub fn is_valid(&self) -> bool {
+        let fields = [
+            &self.event_id[..],
+            &self.league[..],
+            &self.participants[0][..],
+            &self.participants[1][..],
+        ];
+        self.event_id[0] != 0 && fields.iter().all(|field| unpack_str(field).is_some())
+    }
+
+    pub fn unpack(input: &[u8; MarketMetadata::LEN]) -> Self {
+        let (event_id, league, participant_0, participant_1) = array_refs![input, 32, 8, 32, 32];
+        MarketMetadata {
+            event_id: *event_id,
+            league: *league,
+            participants: [*participant_0, *participant_1],
+        }
+    }
+
+    pub fn pack(&self, dst: &mut [u8; MarketMetadata::LEN]) {
+        let (event_id_dst, league_dst, participant_0_dst, participant_1_dst) =
+            mut_array_refs![dst, 32, 8, 32, 32];
+        *event_id_dst = self.event_id;
+        *league_dst = self.league;
+        *participant_0_dst = self.participants[0];
+        *participant_1_dst = self.participants[1];
     }
 }
 
+fn pack_str(src: &str, dst: &mut [u8]) -> Result<(), ProgramError> {
+    if src.len() > dst.len() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    dst[..src.len()].copy_from_slice(src.as_bytes());
+    Ok(())
+}
 
-impl Pack for Market {
-    const LEN: usize = 310;
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, Market::LEN];
+/// Reads a zero padded UTF-8 field.
+pub fn unpack_str(src: &[u8]) -> Option<&str> {
+    let len = src.iter().position(|byte| *byte == 0).unwrap_or(src.len());
+    if src[len..].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    std::str::from_utf8(&src[..len]).ok()
+}
+
+impl FeedChecks {
+    pub const LEN: usize = 16;
+    /// Checks applied to new markets: a round from the last minute answered
+    /// by at least one oracle, and lines matching the feed exactly.
+    pub const DEFAULT: FeedChecks = FeedChecks {
+        max_feed_age_slots: 150,
+        min_oracle_responses: 1,
+        max_line_deviation: 0,
+    };
+
+    pub fn unpack(input: &[u8; FeedChecks::LEN]) -> Self {
+        let (max_feed_age_slots, min_oracle_responses, max_line_deviation) =
+            array_refs![input, 8, 4, 4];
+        FeedChecks {
+            max_feed_age_slots: u64::from_le_bytes(*max_feed_age_slots),
+            min_oracle_responses: u32::from_le_bytes(*min_oracle_responses),
+            max_line_deviation: u32::from_le_bytes(*max_line_deviation),
+        }
+    }
+
+    pub fn pack(&self, dst: &mut [u8; FeedChecks::LEN]) {
+        let (max_feed_age_slots_dst, min_oracle_responses_dst, max_line_deviation_dst) =
+            mut_array_refs![dst, 8, 4, 4];
+        *max_feed_age_slots_dst = self.max_feed_age_slots.to_le_bytes();
+        *min_oracle_responses_dst = self.min_oracle_responses.to_le_bytes();
+        *max_line_deviation_dst = self.max_line_deviation.to_le_bytes();
+    }
+}
+
+impl StakeLimits {
+    pub const LEN: usize = 24;
+    /// Limits applied to new markets: any non zero stake.
+    pub const DEFAULT: StakeLimits = StakeLimits {
+        min_stake: 1,
+        max_stake: u64::MAX,
+        max_payout_per_user: u64::MAX,
+    };
+
+    pub fn unpack(input: &[u8; StakeLimits::LEN]) -> Self {
+        let (min_stake, max_stake, max_payout_per_user) = array_refs![input, 8, 8, 8];
+        StakeLimits {
+            min_stake: u64::from_le_bytes(*min_stake),
+            max_stake: u64::from_le_bytes(*max_stake),
+            max_payout_per_user: u64::from_le_bytes(*max_payout_per_user),
+        }
+    }
+
+    pub fn pack(&self, dst: &mut [u8; StakeLimits::LEN]) {
+        let (min_stake_dst, max_stake_dst, max_payout_per_user_dst) = mut_array_refs![dst, 8, 8, 8];
+        *min_stake_dst = self.min_stake.to_le_bytes();
+        *max_stake_dst = self.max_stake.to_le_bytes();
+        *max_payout_per_user_dst = self.max_payout_per_user.to_le_bytes();
+    }
+}
+
+impl ResultOracle {
+    const LEN: usize = 96;
+
+    fn unpack(input: &[u8; ResultOracle::LEN]) -> Self {
+        let (result_feed, team_a_score_feed, team_b_score_feed) = array_refs![input, 32, 32, 32];
+        ResultOracle {
+            result_feed: Pubkey::new_from_array(*result_feed),
+            team_a_score_feed: Pubkey::new_from_array(*team_a_score_feed),
+            team_b_score_feed: Pubkey::new_from_array(*team_b_score_feed),
+        }
+    }
+
+    fn pack(&self, dst: &mut [u8; ResultOracle::LEN]) {
+        let (result_feed_dst, team_a_score_feed_dst, team_b_score_feed_dst) =
+            mut_array_refs![dst, 32, 32, 32];
+        result_feed_dst.copy_from_slice(self.result_feed.as_ref());
+        team_a_score_feed_dst.copy_from_slice(self.team_a_score_feed.as_ref());
+        team_b_score_feed_dst.copy_from_slice(self.team_b_score_feed.as_ref());
+    }
+}
+
+impl Market {
+    pub const MIN_SIDES: u8 = 2;
+    pub const MAX_SIDES: u8 = 3;
+    pub const MAX_EXTRA_RESULT_ORACLES: usize = 2;
+
+    /// Every oracle that reports on the result, primary oracle first.
+    pub fn result_oracles(&self) -> Vec<ResultOracle> {
+        let mut oracles = Vec::with_capacity(1 + self.extra_result_oracles.len());
+        oracles.push(ResultOracle {
+            result_feed: self.result_feed,
+            team_a_score_feed: self.score_feed_accounts[0],
+            team_b_score_feed: self.score_feed_accounts[1],
+        });
+        oracles.extend_from_slice(&self.extra_result_oracles);
+        oracles
+    }
+
+    /// The sides in play, without the empty draw side of a two-way market.
+    pub fn active_sides(&self) -> &[MarketSide] {
+        &self.market_sides[..self.side_count as usize]
+    }
+}
+
+impl Market {
+    /// Size of a market account.
+    pub const LEN: usize = 889;
+    /// Size of a market account created before markets were tagged
+    /// `AccountType::Market`. Its layout is the first `LEGACY_LEN` bytes of
+    /// the current one, tagged `AccountType::Legacy`. It has no room for the
+    /// dispute window or any per-market configuration, so instructions that
+    /// set them fail until the market is moved over with `MigrateMarket`.
+    pub const LEGACY_LEN: usize = 310;
+
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let market = Market::unpack_unchecked(input)?;
+        if !market.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(market)
+    }
+
+    /// Reads a market in either layout, picked by the account size.
+    pub fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        match input.len() {
+            Market::LEN => Market::unpack_current(array_ref![input, 0, Market::LEN]),
+            Market::LEGACY_LEN => {
+                Market::unpack_base(array_ref![input, 0, Market::LEGACY_LEN], AccountType::Legacy)
+            }
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Writes a market in the layout of the account it is stored in. A legacy
+    /// account only takes the market if nothing outside its layout changed,
+    /// see `unpack_base`.
+    pub fn pack(src: Market, dst: &mut [u8]) -> Result<(), ProgramError> {
+        match dst.len() {
+            Market::LEN => {
+                src.pack_current(array_mut_ref![dst, 0, Market::LEN]);
+                Ok(())
+            }
+            Market::LEGACY_LEN => src.pack_legacy(array_mut_ref![dst, 0, Market::LEGACY_LEN]),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Reads the fields both layouts share. The others are set to what a
+    /// legacy market runs with: three sides, no start time or event, the
+    /// primary result oracle alone with no score feeds, the default feed
+    /// checks and stake limits, and manual settlement by the authority as
+    /// soon as the market commences. Legacy markets keep neither their
+    /// commence time nor a dispute window, so their bets settle right away.
+    fn unpack_base(
+        src: &[u8; Market::LEGACY_LEN],
+        account_type: AccountType,
+    ) -> Result<Self, ProgramError> {
         let (
             is_initialized,
             option_0_odds_pubkey,
@@ -293,13 +766,9 @@ impl Pack for Market {
             pending_bets,
             team_a_score,
             team_b_score,
-            total_score
+            total_score,
         ) = array_refs![src, 1, 33, 33, 8, 8, 33, 33, 8, 8, 33, 33, 8, 8, 8, 32, 1, 8, 8, 2, 2, 2];
-        let is_initialized = match is_initialized {
-            [0] => false,
-            [1] => true,
-            _ => return Err(ProgramError::InvalidAccountData),
-        };
+        let is_initialized = account_type.unpack_initialized(is_initialized)?;
         Ok(Market {
             is_initialized,
             market_sides: [
@@ -324,17 +793,34 @@ impl Pack for Market {
             ],
             locked_liquidity: u64::from_le_bytes(*locked_liquidity),
             result_feed: Pubkey::new_from_array(*result_feed),
-            result: MarketOutcome::unpack(&(u8::from_le_bytes(*result))).unwrap(),
+            score_feed_accounts: [Pubkey::default(); 2],
+            result: MarketOutcome::unpack(&(u8::from_le_bytes(*result)))?,
             bettor_balance: u64::from_le_bytes(*bettor_balance),
             pending_bets: u64::from_le_bytes(*pending_bets),
             team_a_score: u16::from_le_bytes(*team_a_score),
             team_b_score: u16::from_le_bytes(*team_b_score),
             total_score: u16::from_le_bytes(*total_score),
+            start_timestamp: UnixTimestamp::MAX,
+            side_count: Market::MAX_SIDES,
+            metadata: MarketMetadata::unpack(&[0; MarketMetadata::LEN]),
+            dispute_end_slot: 0,
+            extra_result_oracles: Vec::new(),
+            result_quorum: 1,
+            feed_checks: FeedChecks::DEFAULT,
+            commenced_timestamp: 0,
+            manual_settler: authority::ID,
+            manual_settlement_grace_period: 0,
+            stake_limits: StakeLimits::DEFAULT,
+            suspended: false,
+            live_betting: false,
+            live_acceptance_delay_slots: 0,
+            live_odds_feed_accounts: [None; 3],
+            cash_out: false,
+            cash_out_margin_bps: 0,
         })
     }
 
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, Market::LEN];
+    fn pack_base(&self, dst: &mut [u8; Market::LEGACY_LEN], account_type: AccountType) {
         let (
             is_initialized_dst,
             option_0_odds_pubkey_dst,
@@ -359,20 +845,8 @@ impl Pack for Market {
             total_score_dst,
         ) = mut_array_refs![dst, 1, 33, 33, 8, 8, 33, 33, 8, 8, 33, 33, 8, 8, 8, 32, 1, 8, 8, 2, 2, 2];
 
-        let Market {
-            is_initialized,
-            market_sides,
-            locked_liquidity,
-            result_feed,
-            result,
-            bettor_balance,
-            pending_bets,
-            team_a_score,
-            team_b_score,
-            total_score,
-        } = self;
-
-        is_initialized_dst[0] = *is_initialized as u8;
+        let market_sides = &self.market_sides;
+        *is_initialized_dst = account_type.pack_initialized(self.is_initialized);
         pack_pubkey_option(&market_sides[0].odds_feed_account, option_0_odds_pubkey_dst);
         pack_pubkey_option(
             &market_sides[0].points_feed_account,
@@ -394,87 +868,546 @@ impl Pack for Market {
         );
         *option_2_loss_dst = market_sides[2].payout.to_le_bytes();
         *option_2_win_dst = market_sides[2].risk.to_le_bytes();
-        *locked_liquidity_dst = locked_liquidity.to_le_bytes();
-        result_feed_dst.copy_from_slice(result_feed.as_ref());
-        *result_dst = result.pack().to_le_bytes();
-        *bettor_balance_dst = bettor_balance.to_le_bytes();
-        *pending_bets_dst = pending_bets.to_le_bytes();
-        *team_a_score_dst = team_a_score.to_le_bytes();
-        *team_b_score_dst = team_b_score.to_le_bytes();
-        *total_score_dst = total_score.to_le_bytes();
+        *locked_liquidity_dst = self.locked_liquidity.to_le_bytes();
+        result_feed_dst.copy_from_slice(self.result_feed.as_ref());
+        *result_dst = self.result.pack().to_le_bytes();
+        *bettor_balance_dst = self.bettor_balance.to_le_bytes();
+        *pending_bets_dst = self.pending_bets.to_le_bytes();
+        *team_a_score_dst = self.team_a_score.to_le_bytes();
+        *team_b_score_dst = self.team_b_score.to_le_bytes();
+        *total_score_dst = self.total_score.to_le_bytes();
+    }
+
+    fn pack_legacy(&self, dst: &mut [u8; Market::LEGACY_LEN]) -> Result<(), ProgramError> {
+        let mut legacy = [0; Market::LEGACY_LEN];
+        self.pack_base(&mut legacy, AccountType::Legacy);
+        let mut stored = Market::unpack_base(&legacy, AccountType::Legacy)?;
+        stored.dispute_end_slot = self.dispute_end_slot;
+        stored.commenced_timestamp = self.commenced_timestamp;
+        if stored != *self {
+            msg!("Market does not fit its legacy account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        *dst = legacy;
+        Ok(())
+    }
+
+    fn unpack_current(src: &[u8; Market::LEN]) -> Result<Self, ProgramError> {
+        let (
+            base,
+            start_timestamp,
+            side_count,
+            metadata,
+            dispute_end_slot,
+            extra_result_oracle_count,
+            extra_result_oracle_0,
+            extra_result_oracle_1,
+            result_quorum,
+            feed_checks,
+            commenced_timestamp,
+            manual_settler,
+            manual_settlement_grace_period,
+            stake_limits,
+            suspended,
+            live_betting,
+            live_acceptance_delay_slots,
+            live_odds_feed_0,
+            live_odds_feed_1,
+            live_odds_feed_2,
+            team_a_score_feed,
+            team_b_score_feed,
+            cash_out,
+            cash_out_margin_bps,
+        ) = array_refs![src, Market::LEGACY_LEN, 8, 1, MarketMetadata::LEN, 8, 1, ResultOracle::LEN, ResultOracle::LEN, 1, FeedChecks::LEN, 8, 32, 8, StakeLimits::LEN, 1, 1, 8, 33, 33, 33, 32, 32, 1, 2];
+        if side_count[0] > Market::MAX_SIDES {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if extra_result_oracle_count[0] as usize > Market::MAX_EXTRA_RESULT_ORACLES {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let extra_result_oracles = [extra_result_oracle_0, extra_result_oracle_1]
+            .iter()
+            .take(extra_result_oracle_count[0] as usize)
+            .map(|oracle| ResultOracle::unpack(oracle))
+            .collect();
+        Ok(Market {
+            score_feed_accounts: [
+                Pubkey::new_from_array(*team_a_score_feed),
+                Pubkey::new_from_array(*team_b_score_feed),
+            ],
+            start_timestamp: i64::from_le_bytes(*start_timestamp),
+            side_count: side_count[0],
+            metadata: MarketMetadata::unpack(metadata),
+            dispute_end_slot: u64::from_le_bytes(*dispute_end_slot),
+            extra_result_oracles,
+            result_quorum: result_quorum[0],
+            feed_checks: FeedChecks::unpack(feed_checks),
+            commenced_timestamp: i64::from_le_bytes(*commenced_timestamp),
+            manual_settler: Pubkey::new_from_array(*manual_settler),
+            manual_settlement_grace_period: i64::from_le_bytes(*manual_settlement_grace_period),
+            stake_limits: StakeLimits::unpack(stake_limits),
+            suspended: suspended[0] != 0,
+            live_betting: live_betting[0] != 0,
+            live_acceptance_delay_slots: u64::from_le_bytes(*live_acceptance_delay_slots),
+            live_odds_feed_accounts: [
+                unpack_pubkey_option(live_odds_feed_0)?.0,
+                unpack_pubkey_option(live_odds_feed_1)?.0,
+                unpack_pubkey_option(live_odds_feed_2)?.0,
+            ],
+            cash_out: cash_out[0] != 0,
+            cash_out_margin_bps: u16::from_le_bytes(*cash_out_margin_bps),
+            ..Market::unpack_base(base, AccountType::Market)?
+        })
+    }
+
+    fn pack_current(&self, dst: &mut [u8; Market::LEN]) {
+        let (
+            base_dst,
+            start_timestamp_dst,
+            side_count_dst,
+            metadata_dst,
+            dispute_end_slot_dst,
+            extra_result_oracle_count_dst,
+            extra_result_oracle_0_dst,
+            extra_result_oracle_1_dst,
+            result_quorum_dst,
+            feed_checks_dst,
+            commenced_timestamp_dst,
+            manual_settler_dst,
+            manual_settlement_grace_period_dst,
+            stake_limits_dst,
+            suspended_dst,
+            live_betting_dst,
+            live_acceptance_delay_slots_dst,
+            live_odds_feed_0_dst,
+            live_odds_feed_1_dst,
+            live_odds_feed_2_dst,
+            team_a_score_feed_dst,
+            team_b_score_feed_dst,
+            cash_out_dst,
+            cash_out_margin_bps_dst,
+        ) = mut_array_refs![dst, Market::LEGACY_LEN, 8, 1, MarketMetadata::LEN, 8, 1, ResultOracle::LEN, ResultOracle::LEN, 1, FeedChecks::LEN, 8, 32, 8, StakeLimits::LEN, 1, 1, 8, 33, 33, 33, 32, 32, 1, 2];
+
+        let Market {
+            score_feed_accounts,
+            start_timestamp,
+            side_count,
+            metadata,
+            dispute_end_slot,
+            extra_result_oracles,
+            result_quorum,
+            feed_checks,
+            commenced_timestamp,
+            manual_settler,
+            manual_settlement_grace_period,
+            stake_limits,
+            suspended,
+            live_betting,
+            live_acceptance_delay_slots,
+            live_odds_feed_accounts,
+            cash_out,
+            cash_out_margin_bps,
+            ..
+        } = self;
+
+        self.pack_base(base_dst, AccountType::Market);
+        *start_timestamp_dst = start_timestamp.to_le_bytes();
+        side_count_dst[0] = *side_count;
+        metadata.pack(metadata_dst);
+        *dispute_end_slot_dst = dispute_end_slot.to_le_bytes();
+        extra_result_oracle_count_dst[0] = extra_result_oracles.len() as u8;
+        for (index, oracle_dst) in [extra_result_oracle_0_dst, extra_result_oracle_1_dst]
+            .iter_mut()
+            .enumerate()
+        {
+            match extra_result_oracles.get(index) {
+                Some(oracle) => oracle.pack(oracle_dst),
+                None => oracle_dst.fill(0),
+            }
+        }
+        result_quorum_dst[0] = *result_quorum;
+        feed_checks.pack(feed_checks_dst);
+        *commenced_timestamp_dst = commenced_timestamp.to_le_bytes();
+        manual_settler_dst.copy_from_slice(manual_settler.as_ref());
+        *manual_settlement_grace_period_dst = manual_settlement_grace_period.to_le_bytes();
+        stake_limits.pack(stake_limits_dst);
+        suspended_dst[0] = *suspended as u8;
+        live_betting_dst[0] = *live_betting as u8;
+        *live_acceptance_delay_slots_dst = live_acceptance_delay_slots.to_le_bytes();
+        pack_pubkey_option(&live_odds_feed_accounts[0], live_odds_feed_0_dst);
+        pack_pubkey_option(&live_odds_feed_accounts[1], live_odds_feed_1_dst);
+        pack_pubkey_option(&live_odds_feed_accounts[2], live_odds_feed_2_dst);
+        team_a_score_feed_dst.copy_from_slice(score_feed_accounts[0].as_ref());
+        team_b_score_feed_dst.copy_from_slice(score_feed_accounts[1].as_ref());
+        cash_out_dst[0] = *cash_out as u8;
+        *cash_out_margin_bps_dst = cash_out_margin_bps.to_le_bytes();
     }
 }
 
-impl Pack for BettingPoolState {
-    const LEN: usize = 154;
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, BettingPoolState::LEN];
+impl FuturesMarket {
+    pub const MIN_SIDES: usize = 2;
+    const HEADER_LEN: usize = 117;
+    const SIDE_LEN: usize = 49;
+
+    /// Account size needed for a futures market with `side_count` sides.
+    pub fn get_packed_len(side_count: u8) -> usize {
+        FuturesMarket::HEADER_LEN + side_count as usize * FuturesMarket::SIDE_LEN
+    }
+
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let market = FuturesMarket::unpack_unchecked(input)?;
+        if !market.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(market)
+    }
+
+    pub fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < FuturesMarket::HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![input, 0, FuturesMarket::HEADER_LEN];
         let (
             is_initialized,
+            side_count,
             locked_liquidity,
-            live_liquidity,
+            result_feed,
+            result,
+            bettor_balance,
             pending_bets,
-            house_pool_usdt,
-            betting_pool_usdt,
-            insurance_fund_usdt,
-            divvy_foundation_proceeds_usdt,
-            frozen_betting,
-        ) = array_refs![src, 1, 8, 8, 8, 32, 32, 32, 32, 1];
-
-        Ok(BettingPoolState {
-            is_initialized: is_initialized[0] != 0,
+            start_timestamp,
+            feed_checks,
+            stake_limits,
+            suspended,
+            dispute_end_slot,
+        ) = array_refs![src, 1, 1, 8, 32, 2, 8, 8, 8, FeedChecks::LEN, StakeLimits::LEN, 1, 8];
+        let is_initialized = AccountType::FuturesMarket.unpack_initialized(is_initialized)?;
+        if input.len() != FuturesMarket::get_packed_len(side_count[0]) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut market_sides = Vec::with_capacity(side_count[0] as usize);
+        for side in input[FuturesMarket::HEADER_LEN..].chunks_exact(FuturesMarket::SIDE_LEN) {
+            let side = array_ref![side, 0, FuturesMarket::SIDE_LEN];
+            let (odds_pubkey, payout, risk) = array_refs![side, 33, 8, 8];
+            market_sides.push(MarketSide {
+                odds_feed_account: unpack_pubkey_option(odds_pubkey)?.0,
+                points_feed_account: None,
+                payout: u64::from_le_bytes(*payout),
+                risk: u64::from_le_bytes(*risk),
+            });
+        }
+        Ok(FuturesMarket {
+            is_initialized,
+            market_sides,
             locked_liquidity: u64::from_le_bytes(*locked_liquidity),
-            live_liquidity: u64::from_le_bytes(*live_liquidity),
+            result_feed: Pubkey::new_from_array(*result_feed),
+            result: FuturesOutcome::unpack(result)?,
+            bettor_balance: u64::from_le_bytes(*bettor_balance),
             pending_bets: u64::from_le_bytes(*pending_bets),
-            house_pool_usdt: Pubkey::new_from_array(*house_pool_usdt),
-            betting_pool_usdt: Pubkey::new_from_array(*betting_pool_usdt),
-            insurance_fund_usdt: Pubkey::new_from_array(*insurance_fund_usdt),
-            divvy_foundation_proceeds_usdt: Pubkey::new_from_array(*divvy_foundation_proceeds_usdt),
-            frozen_betting: frozen_betting[0] != 0,
+            start_timestamp: i64::from_le_bytes(*start_timestamp),
+            feed_checks: FeedChecks::unpack(feed_checks),
+            stake_limits: StakeLimits::unpack(stake_limits),
+            suspended: suspended[0] != 0,
+            dispute_end_slot: u64::from_le_bytes(*dispute_end_slot),
         })
     }
 
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, BettingPoolState::LEN];
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if self.market_sides.len() > u8::MAX as usize
+            || dst.len() != FuturesMarket::get_packed_len(self.market_sides.len() as u8)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, sides) = dst.split_at_mut(FuturesMarket::HEADER_LEN);
+        let header = array_mut_ref![header, 0, FuturesMarket::HEADER_LEN];
         let (
             is_initialized_dst,
+            side_count_dst,
             locked_liquidity_dst,
-            live_liquidity_dst,
+            result_feed_dst,
+            result_dst,
+            bettor_balance_dst,
             pending_bets_dst,
-            house_pool_usdt_dst,
-            betting_pool_usdt_dst,
-            insurance_fund_usdt_dst,
-            divvy_foundation_proceeds_usdt_dst,
-            frozen_betting_dst,
-        ) = mut_array_refs![dst, 1, 8, 8, 8, 32, 32, 32, 32, 1];
-
-        let BettingPoolState {
+            start_timestamp_dst,
+            feed_checks_dst,
+            stake_limits_dst,
+            suspended_dst,
+            dispute_end_slot_dst,
+        ) = mut_array_refs![header, 1, 1, 8, 32, 2, 8, 8, 8, FeedChecks::LEN, StakeLimits::LEN, 1, 8];
+
+        let FuturesMarket {
             is_initialized,
+            market_sides,
             locked_liquidity,
-            live_liquidity,
+            result_feed,
+            result,
+            bettor_balance,
             pending_bets,
-            house_pool_usdt,
-            betting_pool_usdt,
-            insurance_fund_usdt,
-            divvy_foundation_proceeds_usdt,
-            frozen_betting,
+            start_timestamp,
+            feed_checks,
+            stake_limits,
+            suspended,
+            dispute_end_slot,
         } = self;
-        is_initialized_dst[0] = *is_initialized as u8;
+
+        *is_initialized_dst = AccountType::FuturesMarket.pack_initialized(*is_initialized);
+        side_count_dst[0] = market_sides.len() as u8;
         *locked_liquidity_dst = locked_liquidity.to_le_bytes();
-        *live_liquidity_dst = live_liquidity.to_le_bytes();
+        result_feed_dst.copy_from_slice(result_feed.as_ref());
+        *result_dst = result.pack();
+        *bettor_balance_dst = bettor_balance.to_le_bytes();
         *pending_bets_dst = pending_bets.to_le_bytes();
-        house_pool_usdt_dst.copy_from_slice(house_pool_usdt.as_ref());
-        betting_pool_usdt_dst.copy_from_slice(betting_pool_usdt.as_ref());
-        insurance_fund_usdt_dst.copy_from_slice(insurance_fund_usdt.as_ref());
-        divvy_foundation_proceeds_usdt_dst.copy_from_slice(divvy_foundation_proceeds_usdt.as_ref());
-        frozen_betting_dst[0] = *frozen_betting as u8;
+        *start_timestamp_dst = start_timestamp.to_le_bytes();
+        feed_checks.pack(feed_checks_dst);
+        stake_limits.pack(stake_limits_dst);
+        suspended_dst[0] = *suspended as u8;
+        *dispute_end_slot_dst = dispute_end_slot.to_le_bytes();
+        for (market_side, side_dst) in market_sides
+            .iter()
+            .zip(sides.chunks_exact_mut(FuturesMarket::SIDE_LEN))
+        {
+            let side_dst = array_mut_ref![side_dst, 0, FuturesMarket::SIDE_LEN];
+            let (odds_pubkey_dst, payout_dst, risk_dst) = mut_array_refs![side_dst, 33, 8, 8];
+            pack_pubkey_option(&market_side.odds_feed_account, odds_pubkey_dst);
+            *payout_dst = market_side.payout.to_le_bytes();
+            *risk_dst = market_side.risk.to_le_bytes();
+        }
+        Ok(())
+    }
+}
+
+impl Parlay {
+    pub const MIN_LEGS: usize = 2;
+    /// Bounded by the accounts a transaction can carry, up to three per leg.
+    pub const MAX_LEGS: usize = 8;
+    const HEADER_LEN: usize = 116;
+    const LEG_LEN: usize = 47;
+
+    /// Account size needed for a parlay with `leg_count` legs.
+    pub fn get_packed_len(leg_count: u8) -> usize {
+        Parlay::HEADER_LEN + leg_count as usize * Parlay::LEG_LEN
+    }
+
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let parlay = Parlay::unpack_unchecked(input)?;
+        if !parlay.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(parlay)
+    }
+
+    pub fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < Parlay::HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![input, 0, Parlay::HEADER_LEN];
+        let (
+            is_initialized,
+            user_usdt_account,
+            user_main_account,
+            user_risk,
+            user_payout,
+            outcome,
+            teaser_config,
+            leg_count,
+        ) = array_refs![src, 1, 32, 32, 8, 8, 1, 33, 1];
+        let is_initialized = AccountType::Parlay.unpack_initialized(is_initialized)?;
+        if input.len() != Parlay::get_packed_len(leg_count[0]) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut legs = Vec::with_capacity(leg_count[0] as usize);
+        for leg in input[Parlay::HEADER_LEN..].chunks_exact(Parlay::LEG_LEN) {
+            let leg = array_ref![leg, 0, Parlay::LEG_LEN];
+            let (market, market_side, bet_type, points, decimal_odds, outcome) =
+                array_refs![leg, 32, 1, 1, 4, 8, 1];
+            legs.push(ParlayLeg {
+                market: Pubkey::new_from_array(*market),
+                market_side: market_side[0],
+                bet_type: BetType::unpack(&bet_type[0])?,
+                points: i32::from_le_bytes(*points),
+                decimal_odds: u64::from_le_bytes(*decimal_odds),
+                outcome: BetOutcome::unpack(outcome[0])?,
+            });
+        }
+        Ok(Parlay {
+            is_initialized,
+            user_usdt_account: Pubkey::new_from_array(*user_usdt_account),
+            user_main_account: Pubkey::new_from_array(*user_main_account),
+            user_risk: u64::from_le_bytes(*user_risk),
+            user_payout: u64::from_le_bytes(*user_payout),
+            outcome: outcome[0],
+            teaser_config: unpack_pubkey_option(teaser_config)?.0,
+            legs,
+        })
+    }
+
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if self.legs.len() > Parlay::MAX_LEGS
+            || dst.len() != Parlay::get_packed_len(self.legs.len() as u8)
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, legs) = dst.split_at_mut(Parlay::HEADER_LEN);
+        let header = array_mut_ref![header, 0, Parlay::HEADER_LEN];
+        let (
+            is_initialized_dst,
+            user_usdt_account_dst,
+            user_main_account_dst,
+            user_risk_dst,
+            user_payout_dst,
+            outcome_dst,
+            teaser_config_dst,
+            leg_count_dst,
+        ) = mut_array_refs![header, 1, 32, 32, 8, 8, 1, 33, 1];
+        *is_initialized_dst = AccountType::Parlay.pack_initialized(self.is_initialized);
+        user_usdt_account_dst.copy_from_slice(self.user_usdt_account.as_ref());
+        user_main_account_dst.copy_from_slice(self.user_main_account.as_ref());
+        *user_risk_dst = self.user_risk.to_le_bytes();
+        *user_payout_dst = self.user_payout.to_le_bytes();
+        outcome_dst[0] = self.outcome;
+        pack_pubkey_option(&self.teaser_config, teaser_config_dst);
+        leg_count_dst[0] = self.legs.len() as u8;
+        for (leg, leg_dst) in self.legs.iter().zip(legs.chunks_exact_mut(Parlay::LEG_LEN)) {
+            let leg_dst = array_mut_ref![leg_dst, 0, Parlay::LEG_LEN];
+            let (market_dst, market_side_dst, bet_type_dst, points_dst, decimal_odds_dst, outcome_dst) =
+                mut_array_refs![leg_dst, 32, 1, 1, 4, 8, 1];
+            market_dst.copy_from_slice(leg.market.as_ref());
+            market_side_dst[0] = leg.market_side;
+            bet_type_dst[0] = leg.bet_type.pack();
+            *points_dst = leg.points.to_le_bytes();
+            *decimal_odds_dst = leg.decimal_odds.to_le_bytes();
+            outcome_dst[0] = leg.outcome.map_or(0, |outcome| outcome.pack());
+        }
+        Ok(())
+    }
+}
+
+impl TeaserConfig {
+    pub const ODDS_LEN: usize = Parlay::MAX_LEGS - Parlay::MIN_LEGS + 1;
+
+    /// Decimal odds paid for `leg_count` winning legs, scaled by
+    /// `PARLAY_ODDS_SCALE`. `None` if that many legs are not offered.
+    pub fn decimal_odds(&self, leg_count: usize) -> Option<u64> {
+        let odds = *self.odds.get(leg_count.checked_sub(Parlay::MIN_LEGS)?)?;
+        if odds == 0 {
+            return None;
+        }
+        calculate_decimal_odds(odds as f64)
+    }
+
+    /// Whether the points move the line, every offered odds is a valid
+    /// American price, and more legs never pay less.
+    pub fn is_valid(&self) -> bool {
+        if self.teaser_points <= 0 {
+            return false;
+        }
+        let mut offered = 0;
+        let mut last_decimal_odds = 0;
+        for (index, &odds) in self.odds.iter().enumerate() {
+            if odds == 0 {
+                continue;
+            }
+            if odds.unsigned_abs() < 100 {
+                return false;
+            }
+            match self.decimal_odds(index + Parlay::MIN_LEGS) {
+                Some(decimal_odds) if decimal_odds >= last_decimal_odds => {
+                    last_decimal_odds = decimal_odds;
+                }
+                _ => return false,
+            }
+            offered += 1;
+        }
+        offered > 0
+    }
+}
+
+impl Sealed for TeaserConfig {}
+
+impl IsInitialized for TeaserConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
     }
 }
 
-impl Pack for Bet {
-    const LEN: usize = 118;
+impl Pack for TeaserConfig {
+    const LEN: usize = 61;
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let src = array_ref![src, 0, Bet::LEN];
+        let src = array_ref![src, 0, TeaserConfig::LEN];
+        let (is_initialized, teaser_points, odds_src) = array_refs![src, 1, 4, 56];
+        let is_initialized = AccountType::TeaserConfig.unpack_initialized(is_initialized)?;
+        let mut odds = [0; TeaserConfig::ODDS_LEN];
+        for (odds, src) in odds.iter_mut().zip(odds_src.chunks_exact(8)) {
+            *odds = i64::from_le_bytes(*array_ref![src, 0, 8]);
+        }
+        Ok(TeaserConfig {
+            is_initialized,
+            teaser_points: i32::from_le_bytes(*teaser_points),
+            odds,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, TeaserConfig::LEN];
+        let (is_initialized_dst, teaser_points_dst, odds_dst) = mut_array_refs![dst, 1, 4, 56];
+
+        let TeaserConfig {
+            is_initialized,
+            teaser_points,
+            odds,
+        } = self;
+
+        *is_initialized_dst = AccountType::TeaserConfig.pack_initialized(*is_initialized);
+        *teaser_points_dst = teaser_points.to_le_bytes();
+        for (odds, dst) in odds.iter().zip(odds_dst.chunks_exact_mut(8)) {
+            dst.copy_from_slice(&odds.to_le_bytes());
+        }
+    }
+}
+
+impl Bet {
+    /// `outcome` of a bet sold back with `CashOut`, past the packed `BetOutcome` values.
+    pub const CASHED_OUT: u8 = 7;
+    /// Size of a bet account.
+    pub const LEN: usize = 129;
+    /// Size of a bet account created before bets were tagged
+    /// `AccountType::Bet`. These store their line as a u16 of whole points and
+    /// have no live betting fields. A spread on side 0 stores the points taken
+    /// off team A, the negation of its line.
+    pub const LEGACY_LEN: usize = 118;
+
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let bet = Bet::unpack_unchecked(input)?;
+        if !bet.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(bet)
+    }
+
+    /// Reads a bet in either layout, picked by the account size.
+    pub fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        match input.len() {
+            Bet::LEN => Bet::unpack_current(array_ref![input, 0, Bet::LEN]),
+            Bet::LEGACY_LEN => Bet::unpack_legacy(array_ref![input, 0, Bet::LEGACY_LEN]),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Writes a bet in the layout of the account it is stored in. A legacy
+    /// account only takes a settled, whole point line of a bet that was never
+    /// live.
+    pub fn pack(src: Bet, dst: &mut [u8]) -> Result<(), ProgramError> {
+        match dst.len() {
+            Bet::LEN => {
+                src.pack_current(array_mut_ref![dst, 0, Bet::LEN]);
+                Ok(())
+            }
+            Bet::LEGACY_LEN => src.pack_legacy(array_mut_ref![dst, 0, Bet::LEGACY_LEN]),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    fn unpack_legacy(src: &[u8; Bet::LEGACY_LEN]) -> Result<Self, ProgramError> {
         let (
             is_initialized,
             market,
@@ -487,11 +1420,87 @@ impl Pack for Bet {
             outcome,
             bet_type,
         ) = array_refs![src, 1, 32, 32, 32, 8, 8, 2, 1, 1, 1];
-        let is_initialized = match is_initialized {
-            [0] => false,
-            [1] => true,
-            _ => return Err(ProgramError::InvalidAccountData),
-        };
+        let is_initialized = AccountType::Legacy.unpack_initialized(is_initialized)?;
+        let user_market_side = u8::from_le_bytes(*user_market_side);
+        let bet_type = BetType::unpack(&bet_type[0])?;
+        Ok(Bet {
+            is_initialized,
+            market: Pubkey::new_from_array(*market),
+            user_usdt_account: Pubkey::new_from_array(*user_usdt_account),
+            user_main_account: Pubkey::new_from_array(*user_main_account),
+            user_risk: u64::from_le_bytes(*user_risk),
+            user_payout: u64::from_le_bytes(*user_payout),
+            points: Bet::legacy_points_sign(bet_type, user_market_side)
+                * i32::from(u16::from_le_bytes(*points))
+                * POINTS_SCALE,
+            user_market_side,
+            outcome: u8::from_le_bytes(*outcome),
+            bet_type,
+            pending_acceptance: false,
+            accept_slot: 0,
+        })
+    }
+
+    fn pack_legacy(&self, dst: &mut [u8; Bet::LEGACY_LEN]) -> Result<(), ProgramError> {
+        let (
+            is_initialized_dst,
+            market_dst,
+            user_usdt_account_dst,
+            user_main_account_dst,
+            user_risk_dst,
+            user_payout_dst,
+            points_dst,
+            user_market_side_dst,
+            outcome_dst,
+            bet_type_dst,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 2, 1, 1, 1];
+
+        if self.points % POINTS_SCALE != 0 || self.pending_acceptance || self.accept_slot != 0 {
+            msg!("Bet does not fit its legacy account");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let points = Bet::legacy_points_sign(self.bet_type, self.user_market_side)
+            * (self.points / POINTS_SCALE);
+        let points = u16::try_from(points).map_err(|_| ProgramError::InvalidAccountData)?;
+
+        *is_initialized_dst = AccountType::Legacy.pack_initialized(self.is_initialized);
+        market_dst.copy_from_slice(self.market.as_ref());
+        user_usdt_account_dst.copy_from_slice(self.user_usdt_account.as_ref());
+        user_main_account_dst.copy_from_slice(self.user_main_account.as_ref());
+        *user_risk_dst = self.user_risk.to_le_bytes();
+        *user_payout_dst = self.user_payout.to_le_bytes();
+        *points_dst = points.to_le_bytes();
+        *user_market_side_dst = self.user_market_side.to_le_bytes();
+        *outcome_dst = self.outcome.to_le_bytes();
+        bet_type_dst[0] = self.bet_type.pack();
+        Ok(())
+    }
+
+    /// Legacy side 0 spreads were graded as `team_a_score - points`.
+    fn legacy_points_sign(bet_type: BetType, user_market_side: u8) -> i32 {
+        if bet_type == BetType::Spread && user_market_side == 0 {
+            -1
+        } else {
+            1
+        }
+    }
+
+    fn unpack_current(src: &[u8; Bet::LEN]) -> Result<Self, ProgramError> {
+        let (
+            is_initialized,
+            market,
+            user_usdt_account,
+            user_main_account,
+            user_risk,
+            user_payout,
+            points,
+            user_market_side,
+            outcome,
+            bet_type,
+            pending_acceptance,
+            accept_slot,
+        ) = array_refs![src, 1, 32, 32, 32, 8, 8, 4, 1, 1, 1, 1, 8];
+        let is_initialized = AccountType::Bet.unpack_initialized(is_initialized)?;
         Ok(Bet {
             is_initialized,
             market: Pubkey::new_from_array(*market),
@@ -499,15 +1508,16 @@ impl Pack for Bet {
             user_main_account: Pubkey::new_from_array(*user_main_account),
             user_risk: u64::from_le_bytes(*user_risk),
             user_payout: u64::from_le_bytes(*user_payout),
-            points: u16::from_le_bytes(*points),
+            points: i32::from_le_bytes(*points),
             user_market_side: u8::from_le_bytes(*user_market_side),
             outcome: u8::from_le_bytes(*outcome),
             bet_type: BetType::unpack(&bet_type[0])?,
+            pending_acceptance: pending_acceptance[0] != 0,
+            accept_slot: u64::from_le_bytes(*accept_slot),
         })
     }
 
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, Bet::LEN];
+    fn pack_current(&self, dst: &mut [u8; Bet::LEN]) {
         let (
             is_initialized_dst,
             market_dst,
@@ -519,7 +1529,9 @@ impl Pack for Bet {
             user_market_side_dst,
             outcome_dst,
             bet_type_dst,
-        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 2, 1, 1, 1];
+            pending_acceptance_dst,
+            accept_slot_dst,
+        ) = mut_array_refs![dst, 1, 32, 32, 32, 8, 8, 4, 1, 1, 1, 1, 8];
 
         let Bet {
             is_initialized,
@@ -532,9 +1544,11 @@ impl Pack for Bet {
             user_market_side,
             outcome,
             bet_type,
+            pending_acceptance,
+            accept_slot,
         } = self;
 
-        is_initialized_dst[0] = *is_initialized as u8;
+        *is_initialized_dst = AccountType::Bet.pack_initialized(*is_initialized);
         market_dst.copy_from_slice(market.as_ref());
         user_usdt_account_dst.copy_from_slice(user_usdt_account.as_ref());
         user_main_account_dst.copy_from_slice(user_main_account.as_ref());
@@ -544,6 +1558,74 @@ impl Pack for Bet {
         *user_market_side_dst = user_market_side.to_le_bytes();
         *outcome_dst = outcome.to_le_bytes();
         bet_type_dst[0] = bet_type.pack();
+        pending_acceptance_dst[0] = *pending_acceptance as u8;
+        *accept_slot_dst = accept_slot.to_le_bytes();
+    }
+}
+
+impl Pack for UserExposure {
+    const LEN: usize = 81;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, UserExposure::LEN];
+        let (is_initialized, market, user, risk, payout) = array_refs![src, 1, 32, 32, 8, 8];
+        let is_initialized = AccountType::UserExposure.unpack_initialized(is_initialized)?;
+        Ok(UserExposure {
+            is_initialized,
+            market: Pubkey::new_from_array(*market),
+            user: Pubkey::new_from_array(*user),
+            risk: u64::from_le_bytes(*risk),
+            payout: u64::from_le_bytes(*payout),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, UserExposure::LEN];
+        let (is_initialized_dst, market_dst, user_dst, risk_dst, payout_dst) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8];
+
+        let UserExposure {
+            is_initialized,
+            market,
+            user,
+            risk,
+            payout,
+        } = self;
+
+        *is_initialized_dst = AccountType::UserExposure.pack_initialized(*is_initialized);
+        market_dst.copy_from_slice(market.as_ref());
+        user_dst.copy_from_slice(user.as_ref());
+        *risk_dst = risk.to_le_bytes();
+        *payout_dst = payout.to_le_bytes();
+    }
+}
+
+impl Pack for EventGroup {
+    const LEN: usize = 41;
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let src = array_ref![src, 0, EventGroup::LEN];
+        let (is_initialized, event_id, locked_liquidity) = array_refs![src, 1, 32, 8];
+        let is_initialized = AccountType::EventGroup.unpack_initialized(is_initialized)?;
+        Ok(EventGroup {
+            is_initialized,
+            event_id: *event_id,
+            locked_liquidity: u64::from_le_bytes(*locked_liquidity),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, EventGroup::LEN];
+        let (is_initialized_dst, event_id_dst, locked_liquidity_dst) =
+            mut_array_refs![dst, 1, 32, 8];
+
+        let EventGroup {
+            is_initialized,
+            event_id,
+            locked_liquidity,
+        } = self;
+
+        *is_initialized_dst = AccountType::EventGroup.pack_initialized(*is_initialized);
+        *event_id_dst = *event_id;
+        *locked_liquidity_dst = locked_liquidity.to_le_bytes();
     }
 }
 
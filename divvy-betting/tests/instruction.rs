@@ -0,0 +1,403 @@
+use divvybetting::{
+    instruction::{
+        self, ExchangeInstruction, ParlayLegAccounts, ParlayLegQuote, TeaserLegAccounts,
+        TeaserLegQuote,
+    },
+    schema::token_program_id,
+    state::{BetType, FeedChecks, MarketMetadata, StakeLimits},
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+
+/// One instruction of every variant, with fields away from their defaults so
+/// a field packed at the wrong offset shows up.
+fn instructions() -> Vec<ExchangeInstruction> {
+    vec![
+        ExchangeInstruction::Initbet {
+            risk: 1_000_000,
+            odds: -150,
+            points: -350,
+            market_side: 1,
+            bet_type: BetType::Spread,
+            max_slippage_bps: 250,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::SettleBet { bump_seed: 251 },
+        ExchangeInstruction::InitMarket {
+            start_timestamp: 1_700_000_000,
+            side_count: 2,
+            metadata: MarketMetadata::new("evt-42", "NBA", ["Lakers", "Celtics"]).unwrap(),
+            bump_seed: 251,
+        },
+        ExchangeInstruction::SettleMarket { bump_seed: 251 },
+        ExchangeInstruction::Ownership { bump_seed: 251 },
+        ExchangeInstruction::CommenceMarket { bump_seed: 251 },
+        ExchangeInstruction::Freeze {
+            freeze_betting: true,
+        },
+        ExchangeInstruction::SettlePNL { bump_seed: 251 },
+        ExchangeInstruction::InitFuturesMarket {
+            start_timestamp: 1_700_000_000,
+            side_count: 12,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::InitBust { multiplier: 250 },
+        ExchangeInstruction::InitBustBet {
+            multiplier: 250,
+            risk: 40,
+        },
+        ExchangeInstruction::SettleBustBet {},
+        ExchangeInstruction::VoidMarket { bump_seed: 251 },
+        ExchangeInstruction::RefundBet { bump_seed: 251 },
+        ExchangeInstruction::AddFuturesSides { bump_seed: 251 },
+        ExchangeInstruction::InitFuturesBet {
+            risk: 1_000_000,
+            odds: 900,
+            market_side: 7,
+            max_slippage_bps: 100,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::CommenceFuturesMarket { bump_seed: 251 },
+        ExchangeInstruction::SettleFuturesMarket { bump_seed: 251 },
+        ExchangeInstruction::SettleFuturesBet { bump_seed: 251 },
+        ExchangeInstruction::SettleFuturesPNL { bump_seed: 251 },
+        ExchangeInstruction::CloseMarket { bump_seed: 251 },
+        ExchangeInstruction::OverrideResult {
+            winning_side: 1,
+            team_a_score: 98,
+            team_b_score: 104,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::ConfigureResultOracles {
+            result_quorum: 2,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::ConfigureFeedChecks {
+            feed_checks: FeedChecks {
+                max_feed_age_slots: 300,
+                min_oracle_responses: 3,
+                max_line_deviation: 50,
+            },
+            bump_seed: 251,
+        },
+        ExchangeInstruction::ConfigureManualSettlement {
+            grace_period: 3_600,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::ManualSettleMarket {
+            winning_side: 2,
+            team_a_score: 1,
+            team_b_score: 1,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::ConfigureStakeLimits {
+            stake_limits: StakeLimits {
+                min_stake: 1_000,
+                max_stake: 5_000_000,
+                max_payout_per_user: 20_000_000,
+            },
+            bump_seed: 251,
+        },
+        ExchangeInstruction::ConfigureLiquidityCeilings {
+            max_market_liquidity_bps: 500,
+            max_event_liquidity_bps: 1_500,
+        },
+        ExchangeInstruction::SuspendMarket { bump_seed: 251 },
+        ExchangeInstruction::ResumeMarket { bump_seed: 251 },
+        ExchangeInstruction::ConfigureLiveBetting {
+            live_betting: true,
+            acceptance_delay_slots: 12,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::AcceptLiveBet { bump_seed: 251 },
+        ExchangeInstruction::InitParlay {
+            risk: 1_000_000,
+            max_slippage_bps: 300,
+            legs: vec![
+                ParlayLegQuote {
+                    odds: -110,
+                    points: 0,
+                    market_side: 0,
+                    bet_type: BetType::MoneyLine,
+                },
+                ParlayLegQuote {
+                    odds: 120,
+                    points: 4_550,
+                    market_side: 1,
+                    bet_type: BetType::Total,
+                },
+            ],
+            bump_seed: 251,
+        },
+        ExchangeInstruction::SettleParlay { bump_seed: 251 },
+        ExchangeInstruction::InitTeaserConfig {
+            teaser_points: 600,
+            odds: [-120, 160, 260, 400, 600, 900, 0],
+        },
+        ExchangeInstruction::InitTeaser {
+            risk: 1_000_000,
+            legs: vec![
+                TeaserLegQuote {
+                    points: -750,
+                    market_side: 0,
+                    bet_type: BetType::Spread,
+                },
+                TeaserLegQuote {
+                    points: 4_150,
+                    market_side: 1,
+                    bet_type: BetType::Total,
+                },
+            ],
+            bump_seed: 251,
+        },
+        ExchangeInstruction::ConfigureCashOut {
+            cash_out: true,
+            cash_out_margin_bps: 400,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::CashOut {
+            min_value: 750_000,
+            bump_seed: 251,
+        },
+        ExchangeInstruction::VoidFuturesMarket { bump_seed: 251 },
+        ExchangeInstruction::RefundFuturesBet { bump_seed: 251 },
+        ExchangeInstruction::MigrateBettingPool,
+        ExchangeInstruction::MigrateMarket,
+    ]
+}
+
+#[test]
+fn every_instruction_round_trips() {
+    for instruction in instructions() {
+        let packed = instruction.pack();
+        assert_eq!(
+            ExchangeInstruction::unpack(&packed).unwrap(),
+            instruction,
+            "packed as {:?}",
+            packed
+        );
+    }
+}
+
+#[test]
+fn every_tag_is_covered() {
+    let tags: Vec<u8> = instructions().iter().map(|instruction| instruction.pack()[0]).collect();
+    let mut sorted = tags.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..tags.len() as u8).collect::<Vec<_>>());
+    assert!(ExchangeInstruction::unpack(&[tags.len() as u8]).is_err());
+}
+
+#[test]
+fn truncated_instructions_are_rejected() {
+    for instruction in instructions() {
+        let packed = instruction.pack();
+        if packed.len() > 1 {
+            assert!(
+                ExchangeInstruction::unpack(&packed[..1]).is_err(),
+                "{:?} without its data",
+                instruction
+            );
+        }
+    }
+}
+
+/// Accounts the house liquidity transfer is invoked with.
+struct HouseAccounts {
+    hp_usdt: Pubkey,
+    bet_usdt: Pubkey,
+    house_pda: Pubkey,
+    bet_pda: Pubkey,
+    house_pool_state: Pubkey,
+    divvy_house_program: Pubkey,
+}
+
+/// Every account the program passes on to the divvy house program must be at
+/// least as privileged in the outer instruction, bar the PDA it signs for.
+fn assert_forwards_house_accounts(instruction: &Instruction, house: &HouseAccounts) {
+    let cpi = instruction::transfer_locked_liquidity(
+        &house.divvy_house_program,
+        &token_program_id::ID,
+        &house.house_pda,
+        &house.bet_pda,
+        &house.bet_usdt,
+        &house.hp_usdt,
+        &house.house_pool_state,
+        1,
+        251,
+    );
+    for cpi_meta in cpi.accounts {
+        let meta = instruction
+            .accounts
+            .iter()
+            .find(|meta| meta.pubkey == cpi_meta.pubkey)
+            .unwrap_or_else(|| panic!("{} is not passed", cpi_meta.pubkey));
+        assert!(
+            meta.is_writable || !cpi_meta.is_writable,
+            "{} is not writable",
+            cpi_meta.pubkey
+        );
+    }
+    assert!(instruction
+        .accounts
+        .iter()
+        .any(|meta| meta.pubkey == house.divvy_house_program));
+}
+
+#[test]
+fn bet_builders_forward_house_accounts() {
+    let program_id = Pubkey::new_unique();
+    let house = HouseAccounts {
+        hp_usdt: Pubkey::new_unique(),
+        bet_usdt: Pubkey::new_unique(),
+        house_pda: Pubkey::new_unique(),
+        bet_pda: Pubkey::new_unique(),
+        house_pool_state: Pubkey::new_unique(),
+        divvy_house_program: Pubkey::new_unique(),
+    };
+    let user = Pubkey::new_unique();
+    let feed = Pubkey::new_unique();
+    let bet = Pubkey::new_unique();
+    let market = Pubkey::new_unique();
+    let pool_state = Pubkey::new_unique();
+    let user_usdt = Pubkey::new_unique();
+
+    let instructions = vec![
+        instruction::init_bet(
+            &program_id,
+            &user,
+            &feed,
+            None,
+            &bet,
+            &market,
+            &pool_state,
+            &house.hp_usdt,
+            &house.bet_usdt,
+            &user_usdt,
+            &house.house_pda,
+            &house.bet_pda,
+            &house.house_pool_state,
+            &house.divvy_house_program,
+            "evt-42",
+            1_000_000,
+            -110,
+            0,
+            0,
+            BetType::MoneyLine,
+            100,
+            251,
+        ),
+        instruction::init_futures_bet(
+            &program_id,
+            &user,
+            &feed,
+            &bet,
+            &market,
+            &pool_state,
+            &house.hp_usdt,
+            &house.bet_usdt,
+            &user_usdt,
+            &house.house_pda,
+            &house.bet_pda,
+            &house.house_pool_state,
+            &house.divvy_house_program,
+            1_000_000,
+            900,
+            7,
+            100,
+            251,
+        ),
+        instruction::accept_live_bet(
+            &program_id,
+            &user,
+            &feed,
+            None,
+            &bet,
+            &market,
+            &pool_state,
+            &house.hp_usdt,
+            &house.bet_usdt,
+            &user_usdt,
+            &user,
+            &house.house_pda,
+            &house.bet_pda,
+            &house.house_pool_state,
+            &house.divvy_house_program,
+            "evt-42",
+            251,
+        ),
+        instruction::cash_out(
+            &program_id,
+            &user,
+            &feed,
+            None,
+            &bet,
+            &market,
+            &pool_state,
+            &house.hp_usdt,
+            &house.bet_usdt,
+            &user_usdt,
+            &house.house_pda,
+            &house.bet_pda,
+            &house.house_pool_state,
+            &house.divvy_house_program,
+            "evt-42",
+            750_000,
+            251,
+        ),
+        instruction::init_parlay(
+            &program_id,
+            &user,
+            &bet,
+            &pool_state,
+            &house.hp_usdt,
+            &house.bet_usdt,
+            &user_usdt,
+            &house.house_pda,
+            &house.bet_pda,
+            &house.house_pool_state,
+            &house.divvy_house_program,
+            &[ParlayLegAccounts {
+                market_state_account: market,
+                odds_feed_account: feed,
+                points_feed_account: None,
+            }],
+            &[ParlayLegQuote {
+                odds: -110,
+                points: 0,
+                market_side: 0,
+                bet_type: BetType::MoneyLine,
+            }],
+            1_000_000,
+            300,
+            251,
+        ),
+        instruction::init_teaser(
+            &program_id,
+            &user,
+            &bet,
+            &pool_state,
+            &house.hp_usdt,
+            &house.bet_usdt,
+            &user_usdt,
+            &house.house_pda,
+            &house.bet_pda,
+            &house.house_pool_state,
+            &house.divvy_house_program,
+            &Pubkey::new_unique(),
+            &[TeaserLegAccounts {
+                market_state_account: market,
+                points_feed_account: feed,
+            }],
+            &[TeaserLegQuote {
+                points: -750,
+                market_side: 0,
+                bet_type: BetType::Spread,
+            }],
+            1_000_000,
+            251,
+        ),
+    ];
+    for instruction in instructions {
+        assert_forwards_house_accounts(&instruction.unwrap(), &house);
+    }
+}
@@ -0,0 +1,379 @@
+use divvybetting::{
+    points_from_feed,
+    settlement::{
+        cash_out_value, is_valid_line, parlay_bettor_amount, settle, teased_line,
+        teaser_bettor_amount, BetOutcome, MarketResult,
+    },
+    state::{BetType, ParlayLeg, TeaserConfig},
+    PARLAY_ODDS_SCALE,
+};
+use solana_program::pubkey::Pubkey;
+
+use BetOutcome::{HalfLoss, HalfWin, Loss, Push, Void, Win};
+
+fn result(winning_side: u8, team_a_score: u16, team_b_score: u16) -> MarketResult {
+    MarketResult {
+        winning_side,
+        team_a_score,
+        team_b_score,
+    }
+}
+
+#[test]
+fn moneyline() {
+    // (market side, winning side, outcome)
+    let cases = [
+        (0, 0, Win),
+        (1, 0, Loss),
+        (2, 0, Loss),
+        (0, 1, Loss),
+        (1, 1, Win),
+        (2, 1, Loss),
+        (0, 2, Loss),
+        (1, 2, Loss),
+        (2, 2, Win),
+    ];
+    for &(market_side, winning_side, expected) in cases.iter() {
+        assert_eq!(
+            settle(BetType::MoneyLine, market_side, 0, &result(winning_side, 0, 0)),
+            expected,
+            "moneyline side {} with side {} winning",
+            market_side,
+            winning_side,
+        );
+    }
+}
+
+#[test]
+fn spread() {
+    // (market side, points, team A score, team B score, outcome)
+    let cases = [
+        // Team A giving 3
+        (0, -300, 24, 20, Win),
+        (0, -300, 23, 20, Push),
+        (0, -300, 22, 20, Loss),
+        (0, -300, 20, 20, Loss),
+        (0, -300, 17, 20, Loss),
+        // Team B getting 3
+        (1, 300, 24, 20, Loss),
+        (1, 300, 23, 20, Push),
+        (1, 300, 22, 20, Win),
+        (1, 300, 20, 20, Win),
+        (1, 300, 17, 20, Win),
+        // Half-point lines never push
+        (0, -350, 24, 20, Win),
+        (0, -350, 23, 20, Loss),
+        (1, 350, 24, 20, Loss),
+        (1, 350, 23, 20, Win),
+        // Team B favoured, team A getting 1.5
+        (0, 150, 20, 21, Win),
+        (0, 150, 20, 22, Loss),
+        (1, -150, 20, 22, Win),
+        // Pick'em
+        (0, 0, 21, 20, Win),
+        (0, 0, 20, 20, Push),
+        (1, 0, 20, 20, Push),
+        (1, 0, 20, 21, Win),
+        // Large margins do not overflow
+        (0, i32::MIN, u16::MAX, 0, Loss),
+        (1, i32::MAX, 0, u16::MAX, Win),
+        (1, i32::MIN, u16::MAX, 0, Loss),
+        // Spread has no draw side
+        (2, -300, 24, 20, Void),
+    ];
+    for &(market_side, points, team_a_score, team_b_score, expected) in cases.iter() {
+        assert_eq!(
+            settle(BetType::Spread, market_side, points, &result(0, team_a_score, team_b_score)),
+            expected,
+            "spread side {} on {} with {}-{}",
+            market_side,
+            points,
+            team_a_score,
+            team_b_score,
+        );
+    }
+}
+
+#[test]
+fn spread_ignores_reported_winner() {
+    assert_eq!(settle(BetType::Spread, 1, 300, &result(0, 22, 20)), Win);
+    assert_eq!(settle(BetType::Spread, 1, 300, &result(1, 22, 20)), Win);
+}
+
+#[test]
+fn total() {
+    // (market side, points, team A score, team B score, outcome)
+    let cases = [
+        // Under
+        (0, 4500, 20, 20, Win),
+        (0, 4500, 25, 20, Push),
+        (0, 4500, 30, 20, Loss),
+        // Over
+        (1, 4500, 20, 20, Loss),
+        (1, 4500, 25, 20, Push),
+        (1, 4500, 30, 20, Win),
+        // Half-point lines never push
+        (0, 250, 1, 1, Win),
+        (1, 250, 1, 1, Loss),
+        (0, 250, 2, 1, Loss),
+        (1, 250, 2, 1, Win),
+        // Zero line
+        (0, 0, 0, 0, Push),
+        (1, 0, 1, 0, Win),
+        // Totals above u16::MAX
+        (1, 13_106_950, u16::MAX, u16::MAX, Win),
+        (0, 13_106_950, u16::MAX, u16::MAX, Loss),
+        // Negative lines are always beaten
+        (1, -50, 0, 0, Win),
+        // Totals have no third side
+        (2, 4500, 25, 20, Void),
+    ];
+    for &(market_side, points, team_a_score, team_b_score, expected) in cases.iter() {
+        assert_eq!(
+            settle(BetType::Total, market_side, points, &result(0, team_a_score, team_b_score)),
+            expected,
+            "total side {} on {} with {}-{}",
+            market_side,
+            points,
+            team_a_score,
+            team_b_score,
+        );
+    }
+}
+
+#[test]
+fn asian_handicap() {
+    // (market side, points, team A score, team B score, outcome)
+    let cases = [
+        // Team A -0.25: half on 0, half on -0.5
+        (0, -25, 1, 0, Win),
+        (0, -25, 0, 0, HalfLoss),
+        (0, -25, 0, 1, Loss),
+        // Team B +0.25: half on 0, half on +0.5
+        (1, 25, 1, 0, Loss),
+        (1, 25, 0, 0, HalfWin),
+        (1, 25, 0, 1, Win),
+        // Team A -0.75: half on -0.5, half on -1
+        (0, -75, 2, 0, Win),
+        (0, -75, 1, 0, HalfWin),
+        (0, -75, 0, 0, Loss),
+        // Team B +0.75: half on +0.5, half on +1
+        (1, 75, 2, 0, Loss),
+        (1, 75, 1, 0, HalfLoss),
+        (1, 75, 0, 0, Win),
+        // Team A -1.25: half on -1, half on -1.5
+        (0, -125, 2, 0, Win),
+        (0, -125, 1, 0, HalfLoss),
+        // Whole and half lines settle like a spread
+        (0, -100, 1, 0, Push),
+        (0, -50, 1, 0, Win),
+        (1, 50, 1, 0, Loss),
+        // Asian handicap has no draw side
+        (2, -25, 1, 0, Void),
+    ];
+    for &(market_side, points, team_a_score, team_b_score, expected) in cases.iter() {
+        assert_eq!(
+            settle(
+                BetType::AsianHandicap,
+                market_side,
+                points,
+                &result(0, team_a_score, team_b_score),
+            ),
+            expected,
+            "asian handicap side {} on {} with {}-{}",
+            market_side,
+            points,
+            team_a_score,
+            team_b_score,
+        );
+    }
+}
+
+#[test]
+fn valid_lines() {
+    assert!(is_valid_line(BetType::MoneyLine, 0));
+    assert!(!is_valid_line(BetType::MoneyLine, -350));
+    assert!(is_valid_line(BetType::Spread, -350));
+    assert!(is_valid_line(BetType::Total, 4510));
+    assert!(is_valid_line(BetType::AsianHandicap, -25));
+    assert!(is_valid_line(BetType::AsianHandicap, 150));
+    assert!(!is_valid_line(BetType::AsianHandicap, -30));
+}
+
+#[test]
+fn points_from_feed_rounds_to_hundredths() {
+    assert_eq!(points_from_feed(-3.5), Some(-350));
+    assert_eq!(points_from_feed(2.5), Some(250));
+    assert_eq!(points_from_feed(2.25), Some(225));
+    assert_eq!(points_from_feed(44.999_999), Some(4500));
+    assert_eq!(points_from_feed(0.0), Some(0));
+    assert_eq!(points_from_feed(f64::NAN), None);
+    assert_eq!(points_from_feed(f64::INFINITY), None);
+    assert_eq!(points_from_feed(1e10), None);
+}
+
+#[test]
+fn outcome_packing() {
+    assert_eq!(Win.pack(), 1);
+    assert_eq!(Loss.pack(), 2);
+    assert_eq!(Push.pack(), 3);
+    assert_eq!(Void.pack(), 4);
+    assert_eq!(HalfWin.pack(), 5);
+    assert_eq!(HalfLoss.pack(), 6);
+    assert!(Push.is_refund() && Void.is_refund());
+    assert!(!Win.is_refund() && !Loss.is_refund());
+    assert!(!HalfWin.is_refund() && !HalfLoss.is_refund());
+}
+
+#[test]
+fn bettor_amount() {
+    // (outcome, risk, payout, amount paid back)
+    let cases = [
+        (Win, 100, 90, Some(190)),
+        (HalfWin, 100, 90, Some(145)),
+        (Push, 100, 90, Some(100)),
+        (Void, 100, 90, Some(100)),
+        (HalfLoss, 100, 90, Some(50)),
+        (Loss, 100, 90, Some(0)),
+        (HalfLoss, 101, 90, Some(50)),
+        (Win, u64::MAX, 1, None),
+    ];
+    for &(outcome, risk, payout, expected) in cases.iter() {
+        assert_eq!(outcome.bettor_amount(risk, payout), expected, "{:?}", outcome);
+    }
+}
+
+fn leg(decimal_odds: u64, outcome: Option<BetOutcome>) -> ParlayLeg {
+    ParlayLeg {
+        market: Pubkey::default(),
+        market_side: 0,
+        bet_type: BetType::MoneyLine,
+        points: 0,
+        decimal_odds,
+        outcome,
+    }
+}
+
+#[test]
+fn parlay_amount() {
+    let evens = 2 * PARLAY_ODDS_SCALE;
+    let three = 3 * PARLAY_ODDS_SCALE;
+    // (legs, amount paid back on a risk of 100)
+    let cases = [
+        // Placement counts every leg as won
+        (vec![leg(evens, None), leg(three, None)], Some(600)),
+        (vec![leg(evens, Some(Win)), leg(three, Some(Win))], Some(600)),
+        // Void and pushed legs drop out
+        (vec![leg(evens, Some(Void)), leg(three, Some(Win))], Some(300)),
+        (vec![leg(evens, Some(Push)), leg(three, Some(Void))], Some(100)),
+        // Half-settled legs carry half the stake through their odds
+        (vec![leg(evens, Some(HalfWin)), leg(three, Some(Win))], Some(450)),
+        (vec![leg(evens, Some(HalfLoss)), leg(three, Some(Win))], Some(150)),
+        // Any losing leg loses the parlay
+        (vec![leg(evens, Some(Loss)), leg(three, None)], Some(0)),
+        // -110 legs round down
+        (vec![leg(1_909_090, Some(Win)), leg(1_909_090, Some(Win))], Some(364)),
+    ];
+    for (legs, expected) in cases.iter() {
+        assert_eq!(parlay_bettor_amount(100, legs), *expected);
+    }
+    assert_eq!(parlay_bettor_amount(u64::MAX, &[leg(three, None)]), None);
+}
+
+fn teaser_config(odds: [i64; TeaserConfig::ODDS_LEN]) -> TeaserConfig {
+    TeaserConfig {
+        is_initialized: true,
+        teaser_points: 600,
+        odds,
+    }
+}
+
+#[test]
+fn teased_lines() {
+    assert_eq!(teased_line(BetType::Spread, 0, -750, 600), Some(-150));
+    assert_eq!(teased_line(BetType::Spread, 1, 250, 600), Some(850));
+    assert_eq!(teased_line(BetType::Total, 0, 4500, 600), Some(5100));
+    assert_eq!(teased_line(BetType::Total, 1, 4500, 600), Some(3900));
+    assert_eq!(teased_line(BetType::Spread, 2, 250, 600), None);
+    assert_eq!(teased_line(BetType::MoneyLine, 0, 0, 600), None);
+    assert_eq!(teased_line(BetType::AsianHandicap, 0, -25, 600), None);
+    assert_eq!(teased_line(BetType::Spread, 0, i32::MAX, 600), None);
+}
+
+#[test]
+fn teaser_config_terms() {
+    let config = teaser_config([-110, 180, 300, 0, 0, 0, 0]);
+    assert!(config.is_valid());
+    assert_eq!(config.decimal_odds(1), None);
+    assert_eq!(config.decimal_odds(3), Some(2_800_000));
+    assert_eq!(config.decimal_odds(5), None);
+    assert_eq!(config.decimal_odds(9), None);
+    // Nothing offered
+    assert!(!teaser_config([0; TeaserConfig::ODDS_LEN]).is_valid());
+    // Not an American price
+    assert!(!teaser_config([50, 0, 0, 0, 0, 0, 0]).is_valid());
+    // More legs paying less
+    assert!(!teaser_config([180, -110, 0, 0, 0, 0, 0]).is_valid());
+    // The line has to move
+    let mut config = teaser_config([-110, 0, 0, 0, 0, 0, 0]);
+    config.teaser_points = 0;
+    assert!(!config.is_valid());
+}
+
+#[test]
+fn teaser_amount() {
+    let config = teaser_config([-120, 160, 0, 0, 0, 0, 0]);
+    // Teaser legs carry no odds of their own
+    let tease = |outcome| leg(0, outcome);
+    // (legs, amount paid back on a risk of 120)
+    let cases = [
+        // Placement counts every leg as won
+        (vec![tease(None), tease(None), tease(None)], Some(312)),
+        // -120 rounds down
+        (vec![tease(Some(Win)), tease(Some(Win))], Some(219)),
+        // A pushed leg drops the teaser to the next leg count
+        (vec![tease(Some(Push)), tease(Some(Win)), tease(Some(Win))], Some(219)),
+        // Too few legs left is a refund
+        (vec![tease(Some(Void)), tease(Some(Win))], Some(120)),
+        // Any losing leg loses the teaser
+        (vec![tease(Some(Loss)), tease(Some(Win)), tease(Some(Win))], Some(0)),
+    ];
+    for (legs, expected) in cases.iter() {
+        assert_eq!(teaser_bettor_amount(120, legs, &config), *expected);
+    }
+    // Leg counts that are not offered are refunded
+    let config = teaser_config([0, 160, 0, 0, 0, 0, 0]);
+    assert_eq!(teaser_bettor_amount(120, &[tease(Some(Win)), tease(Some(Win))], &config), Some(120));
+}
+
+#[test]
+fn cash_out() {
+    let evens = 2 * PARLAY_ODDS_SCALE;
+    // (risk, payout, current decimal odds, margin bps, value)
+    let cases = [
+        // Odds unchanged: the risk back, less the margin
+        (100, 100, evens, 0, Some(100)),
+        (100, 100, evens, 500, Some(95)),
+        // Odds shortened to 1.25, the bet is worth more than its risk
+        (100, 100, 1_250_000, 0, Some(160)),
+        // Odds drifted to 4.0
+        (100, 100, 4 * PARLAY_ODDS_SCALE, 0, Some(50)),
+        // Certain to win is worth the full win amount
+        (100, 90, PARLAY_ODDS_SCALE, 0, Some(190)),
+        // Odds below 1.0 or a margin above 100% cannot be priced
+        (100, 100, 999_999, 0, None),
+        (100, 100, evens, 10_001, None),
+        (u64::MAX, 1, evens, 0, None),
+    ];
+    for &(risk, payout, decimal_odds, margin_bps, expected) in cases.iter() {
+        assert_eq!(
+            cash_out_value(risk, payout, decimal_odds, margin_bps),
+            expected,
+            "{} + {} at {} less {} bps",
+            risk,
+            payout,
+            decimal_odds,
+            margin_bps,
+        );
+    }
+}
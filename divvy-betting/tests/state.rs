@@ -0,0 +1,174 @@
+use divvybetting::{
+    schema::authority,
+    settlement::{settle, BetOutcome, MarketResult},
+    state::{AccountType, Bet, BetType, BettingPoolState, Market, StakeLimits},
+};
+use solana_program::pubkey::Pubkey;
+
+const LEGACY_LOCKED_LIQUIDITY: usize = 247;
+const LEGACY_RESULT: usize = 287;
+const LEGACY_BET_POINTS: usize = 113;
+const LEGACY_BET_SIDE: usize = 115;
+
+fn legacy_market() -> [u8; Market::LEGACY_LEN] {
+    let mut data = [0; Market::LEGACY_LEN];
+    data[0] = 1;
+    data[LEGACY_LOCKED_LIQUIDITY..LEGACY_LOCKED_LIQUIDITY + 8].copy_from_slice(&500u64.to_le_bytes());
+    // Not yet commenced
+    data[LEGACY_RESULT] = 3;
+    data
+}
+
+fn legacy_bet(points: u16, market_side: u8) -> [u8; Bet::LEGACY_LEN] {
+    let mut data = [0; Bet::LEGACY_LEN];
+    data[0] = 1;
+    data[LEGACY_BET_POINTS..LEGACY_BET_POINTS + 2].copy_from_slice(&points.to_le_bytes());
+    data[LEGACY_BET_SIDE] = market_side;
+    // Spread
+    data[Bet::LEGACY_LEN - 1] = 1;
+    data
+}
+
+#[test]
+fn legacy_market_round_trips() {
+    let data = legacy_market();
+    let market = Market::unpack(&data).unwrap();
+    assert_eq!(market.locked_liquidity, 500);
+    assert_eq!(market.side_count, Market::MAX_SIDES);
+    assert_eq!(market.start_timestamp, i64::MAX);
+    assert_eq!(market.manual_settler, authority::ID);
+    assert_eq!(market.manual_settlement_grace_period, 0);
+
+    let mut packed = [0; Market::LEGACY_LEN];
+    Market::pack(market, &mut packed).unwrap();
+    assert_eq!(packed, data);
+}
+
+#[test]
+fn legacy_market_has_no_dispute_window() {
+    let mut market = Market::unpack(&legacy_market()).unwrap();
+    market.dispute_end_slot = 100;
+    let mut packed = legacy_market();
+    Market::pack(market, &mut packed).unwrap();
+    assert_eq!(Market::unpack(&packed).unwrap().dispute_end_slot, 0);
+}
+
+#[test]
+fn legacy_market_rejects_fields_it_cannot_store() {
+    let mut market = Market::unpack(&legacy_market()).unwrap();
+    market.stake_limits = StakeLimits {
+        min_stake: 5,
+        ..StakeLimits::DEFAULT
+    };
+    assert!(Market::pack(market, &mut legacy_market()).is_err());
+}
+
+#[test]
+fn migrated_market_keeps_its_state() {
+    let mut market = Market::unpack(&legacy_market()).unwrap();
+    market.dispute_end_slot = 100;
+    market.stake_limits = StakeLimits {
+        min_stake: 5,
+        ..StakeLimits::DEFAULT
+    };
+
+    let mut migrated = [0; Market::LEN];
+    Market::pack(market, &mut migrated).unwrap();
+    assert_eq!(AccountType::of(&migrated).unwrap(), AccountType::Market);
+    let market = Market::unpack(&migrated).unwrap();
+    assert_eq!(market.locked_liquidity, 500);
+    assert_eq!(market.dispute_end_slot, 100);
+    assert_eq!(market.stake_limits.min_stake, 5);
+}
+
+#[test]
+fn current_market_is_tagged() {
+    let mut market = Market::unpack_unchecked(&[0; Market::LEN]).unwrap();
+    assert!(!market.is_initialized);
+    market.is_initialized = true;
+    market.result_feed = Pubkey::new_unique();
+    let result_feed = market.result_feed;
+
+    let mut data = [0; Market::LEN];
+    Market::pack(market, &mut data).unwrap();
+    assert_eq!(AccountType::of(&data).unwrap(), AccountType::Market);
+    assert_eq!(Market::unpack(&data).unwrap().result_feed, result_feed);
+}
+
+#[test]
+fn accounts_of_other_types_are_not_markets() {
+    let mut data = [0; Market::LEN];
+    data[0] = AccountType::Bet as u8;
+    assert!(Market::unpack(&data).is_err());
+}
+
+#[test]
+fn legacy_bet_line_is_whole_points() {
+    let data = legacy_bet(3, 1);
+    let bet = Bet::unpack(&data).unwrap();
+    assert_eq!(bet.points, 300);
+    assert_eq!(bet.bet_type, BetType::Spread);
+    assert!(!bet.pending_acceptance);
+
+    let mut packed = [0; Bet::LEGACY_LEN];
+    Bet::pack(bet, &mut packed).unwrap();
+    assert_eq!(packed, data);
+
+    let mut bet = Bet::unpack(&data).unwrap();
+    bet.points = 350;
+    assert!(Bet::pack(bet, &mut packed).is_err());
+}
+
+#[test]
+fn legacy_favourite_spread_gives_points() {
+    // Side 0 stored the 3 points taken off team A.
+    let data = legacy_bet(3, 0);
+    let bet = Bet::unpack(&data).unwrap();
+    assert_eq!(bet.points, -300);
+
+    let result = |team_a_score, team_b_score| MarketResult {
+        winning_side: 0,
+        team_a_score,
+        team_b_score,
+    };
+    let outcome = |result| settle(bet.bet_type, bet.user_market_side, bet.points, &result);
+    assert_eq!(outcome(result(10, 6)), BetOutcome::Win);
+    assert_eq!(outcome(result(8, 6)), BetOutcome::Loss);
+
+    let mut packed = [0; Bet::LEGACY_LEN];
+    Bet::pack(bet, &mut packed).unwrap();
+    assert_eq!(packed, data);
+}
+
+#[test]
+fn legacy_betting_pool_has_no_liquidity_ceilings() {
+    let mut data = [0; BettingPoolState::LEGACY_LEN];
+    data[0] = 1;
+    let pool_state = BettingPoolState::unpack(&data).unwrap();
+    assert_eq!(pool_state.max_market_liquidity_bps, BettingPoolState::NO_LIQUIDITY_CEILING_BPS);
+    assert_eq!(pool_state.max_event_liquidity_bps, BettingPoolState::NO_LIQUIDITY_CEILING_BPS);
+
+    let mut packed = [0; BettingPoolState::LEGACY_LEN];
+    BettingPoolState::pack(pool_state, &mut packed).unwrap();
+    assert_eq!(packed, data);
+
+    let mut pool_state = BettingPoolState::unpack(&data).unwrap();
+    pool_state.max_market_liquidity_bps = 500;
+    assert!(BettingPoolState::pack(pool_state, &mut packed).is_err());
+}
+
+#[test]
+fn migrated_betting_pool_keeps_its_state() {
+    let mut data = [0; BettingPoolState::LEGACY_LEN];
+    data[0] = 1;
+    data[1..9].copy_from_slice(&700u64.to_le_bytes());
+    let mut pool_state = BettingPoolState::unpack(&data).unwrap();
+    pool_state.max_market_liquidity_bps = 500;
+
+    let mut migrated = [0; BettingPoolState::LEN];
+    BettingPoolState::pack(pool_state, &mut migrated).unwrap();
+    assert_eq!(AccountType::of(&migrated).unwrap(), AccountType::BettingPoolState);
+    let pool_state = BettingPoolState::unpack(&migrated).unwrap();
+    assert_eq!(pool_state.locked_liquidity, 700);
+    assert_eq!(pool_state.max_market_liquidity_bps, 500);
+}
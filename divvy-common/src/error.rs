@@ -0,0 +1,631 @@
+use num_derive::FromPrimitive as DeriveFromPrimitive;
+use num_traits::FromPrimitive as TraitsFromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Copy, Clone, DeriveFromPrimitive, PartialEq, Eq)]
+pub enum ExchangeError {
+    /// Invalid instruction
+    #[error("Invalid Instruction")]
+    InvalidInstruction,
+    /// Not Valid Authority
+    #[error("Not Valid Authority")]
+    NotValidAuthority,
+    /// Expected Amount Mismatch
+    #[error("Expected Amount Mismatch")]
+    ExpectedAmountMismatch,
+    /// Payout Zero
+    #[error("Payout came out as Zero, please  debug")]
+    PayoutZero,
+    /// Expected Data Mismatch
+    #[error("Expected Data Mismatch")]
+    ExpectedDataMismatch,
+    /// Amount Overflow
+    #[error("Amount Overflow")]
+    AmountOverflow,
+    /// Invalid feed account
+    #[error("Invalid feed account")]
+    InvalidFeedAccount,
+    #[error("Invalid house token mint account")]
+    InvalidHtMintAccount,
+    #[error("Invalid house pool USDT account")]
+    InvalidHousePoolUsdtAccount,
+    #[error("Invalid betting pool USDT account")]
+    InvalidBettingPoolUsdtAccount,
+    #[error("Invalid market account")]
+    InvalidMarketAccount,
+    #[error("Invalid insurance fund USDT account")]
+    InvalidInsuranceFundUsdtAccount,
+    #[error("Invalid divvy foundation USDT account")]
+    InvalidDivvyFoundationUsdtAccount,
+
+    // Deposit withdraw errors
+    #[error("Not enough available liquidity for withdrawal")]
+    NotEnoughAvailableLiquidityForWithdrawal,
+    #[error("Can not use the house pool when there are bets placed on live games")]
+    GamesAreLive,
+    #[error("Pool is frozen")]
+    PoolFrozen,
+
+    // Betting errors
+    #[error("Betting is frozen")]
+    BettingFrozen,
+
+    // Already settled errors
+    #[error("Market already settled")]
+    MarketAlreadySettled,
+    #[error("Market not settled")]
+    MarketNotSettled,
+    #[error("Bet already settled")]
+    BetAlreadySettled,
+
+    // Betting init errors
+    #[error("Not enough available liquidity for bet")]
+    NotEnoughAvailableLiquidityForBet,
+    #[error("Bet risk is zero")]
+    BetRiskZero,
+
+    // Market settlement errors
+    #[error("Feed result not valid when settling market")]
+    NotValidMarketResult,
+
+    // Market commence errors
+    #[error("Market has already commenced")]
+    MarketCommenced,
+
+    // Initialized errors
+    #[error("HP liquidity not initialized")]
+    BettingPoolStateNotInitialized,
+    #[error("HP liquidity already initialized")]
+    BettingPoolStateAlreadyInitialized,
+    #[error("Market not initialized")]
+    MarketNotInitialized,
+    #[error("Market already initialized")]
+    MarketAlreadyInitialized,
+    #[error("Bet already initialized")]
+    BetAlreadyInitialized,
+    #[error("Feed not initialized")]
+    FeedNotInitialized,
+
+    // Assertion errors
+    #[error("Market side risk underflow.")]
+    MarketSideRiskUnderflow,
+    #[error("Market side payout underflow.")]
+    MarketSidePayoutUnderflow,
+    #[error("All bets in market settled and market side risk is positive.")]
+    MarketSideRiskRemaining,
+    #[error("All bets in market settled and market side payout is positive.")]
+    MarketSidePayoutRemaining,
+    #[error("All bets in market settled and market bettor balance is positive.")]
+    MarketBettorBalanceRemaining,
+    #[error("All bets settled and house pool bettor balance is positive.")]
+    HousePoolBettorBalanceRemaining,
+    #[error("All bets settled and the locked liquidity in the house pool is positive.")]
+    HousePoolLockedLiquidityRemaining,
+    #[error("All bets settled and the live liquidity in the house pool is positive.")]
+    HousePoolLiveLiquidityRemaining,
+
+    // Variants below were added after release. Append new ones at the end so
+    // existing error codes keep their values.
+
+    // Betting init errors
+    #[error("Feed odds moved beyond the accepted slippage")]
+    OddsSlippageExceeded,
+
+    // Initialized errors
+    #[error("House pool state not initialized")]
+    HpLiquidityNotInitialized,
+    #[error("House pool state already initialized")]
+    HpLiquidityAlreadyInitialized,
+
+    // Market void errors
+    #[error("Market voided")]
+    MarketVoided,
+    #[error("Market not voided")]
+    MarketNotVoided,
+
+    // Market commence errors
+    #[error("Market start time has passed")]
+    MarketStarted,
+
+    // Betting init errors
+    #[error("Invalid market side")]
+    InvalidMarketSide,
+
+    // Market void errors
+    #[error("Market has unsettled bets")]
+    MarketBetsPending,
+
+    // Market status errors
+    #[error("Instruction not allowed in the current market status")]
+    InvalidMarketStatus,
+
+    // Betting init errors
+    #[error("Invalid market metadata")]
+    InvalidMarketMetadata,
+
+    // Market settlement errors
+    #[error("Market result is still in its dispute window")]
+    DisputeWindowOpen,
+    #[error("Market result dispute window has closed")]
+    DisputeWindowClosed,
+    #[error("Result feeds disagree and no quorum was reached")]
+    OracleQuorumNotReached,
+    #[error("Result quorum must be a majority of the registered result feeds")]
+    InvalidOracleQuorum,
+
+    // Oracle errors
+    #[error("Feed round is older than the market allows")]
+    StaleFeed,
+    #[error("Feed round has fewer oracle responses than the market requires")]
+    InsufficientOracleResponses,
+
+    // Market settlement errors
+    #[error("Manual settlement grace period has not passed")]
+    ManualSettlementTooEarly,
+
+    // Betting init errors
+    #[error("Bet risk is below the market minimum stake")]
+    StakeBelowMinimum,
+    #[error("Bet risk is above the market maximum stake")]
+    StakeAboveMaximum,
+    #[error("Bet takes the user's payout on the market above its cap")]
+    UserExposureExceeded,
+    #[error("Invalid user exposure account")]
+    InvalidUserExposureAccount,
+    #[error("Bet takes the market's locked liquidity above its share of the house pool")]
+    MarketLiquidityCeilingExceeded,
+    #[error("Bet takes the event's locked liquidity above its share of the house pool")]
+    EventLiquidityCeilingExceeded,
+    #[error("Invalid event group account")]
+    InvalidEventGroupAccount,
+
+    // Market commence errors
+    #[error("Betting on this market is suspended")]
+    MarketSuspended,
+
+    // Live betting errors
+    #[error("Bet is not waiting for acceptance")]
+    LiveBetNotPending,
+    #[error("Live bet acceptance delay has not passed")]
+    LiveBetAcceptanceDelay,
+
+    // Betting init errors
+    #[error("Bet line is outside the tolerance of the points feed")]
+    LineMismatch,
+    #[error("Bet line is not valid for the bet type")]
+    InvalidBetLine,
+
+    // Parlay errors
+    #[error("Invalid number of parlay legs")]
+    InvalidParlayLegCount,
+    #[error("Parlay legs must be on different markets")]
+    DuplicateParlayMarket,
+    #[error("Invalid parlay account")]
+    InvalidParlayAccount,
+    #[error("Teaser legs must be spread or total bets")]
+    InvalidTeaserLeg,
+    #[error("Invalid teaser config account")]
+    InvalidTeaserConfigAccount,
+    #[error("Teaser points or odds table not valid")]
+    InvalidTeaserConfig,
+
+    // Cash out errors
+    #[error("Bet cannot be cashed out")]
+    CashOutNotAvailable,
+}
+
+impl PrintProgramError for ExchangeError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + TraitsFromPrimitive,
+    {
+        match self {
+            ExchangeError::InvalidInstruction => msg!("Invalid Instruction"),
+            ExchangeError::NotValidAuthority => msg!("Not Valid Authority"),
+            ExchangeError::ExpectedAmountMismatch => msg!("Expected Amount Mismatch"),
+            ExchangeError::PayoutZero => msg!("Payout cameout as Zero"),
+            ExchangeError::ExpectedDataMismatch => msg!("Expected Data Mismatch"),
+            ExchangeError::AmountOverflow => msg!("Amount Overflow"),
+            ExchangeError::InvalidFeedAccount => msg!("Invalid feed account"),
+            ExchangeError::InvalidHtMintAccount => msg!("Invalid house token mint account"),
+            ExchangeError::InvalidHousePoolUsdtAccount => msg!("Invalid house pool USDT account"),
+            ExchangeError::InvalidBettingPoolUsdtAccount => msg!("Invalid betting pool USDT account"),
+            ExchangeError::InvalidMarketAccount => msg!("Invalid market account"),
+            ExchangeError::InvalidInsuranceFundUsdtAccount => {
+                msg!("Invalid insurance fund USDT account")
+            }
+            ExchangeError::InvalidDivvyFoundationUsdtAccount => {
+                msg!("Invalid divvy foundation USDT account")
+            }
+
+            // Deposit withdraw errors
+            ExchangeError::NotEnoughAvailableLiquidityForWithdrawal => {
+                msg!("Not enough available liquidity for withdrawal")
+            }
+            ExchangeError::GamesAreLive => {
+                msg!("Can not use the house pool when there are bets placed on live games")
+            }
+            ExchangeError::PoolFrozen => msg!("Pool is frozen"),
+
+            // Betting errors
+            ExchangeError::BettingFrozen => msg!("Betting is frozen"),
+
+            // Settled errors
+            ExchangeError::MarketAlreadySettled => msg!("Market already settled"),
+            ExchangeError::MarketNotSettled => msg!("Market not settled"),
+            ExchangeError::BetAlreadySettled => msg!("Bet already settled"),
+
+            // Betting init errors
+            ExchangeError::NotEnoughAvailableLiquidityForBet => {
+                msg!("Not enough available liquidity for bet")
+            }
+            ExchangeError::BetRiskZero => msg!("Bet risk is zero"),
+
+            // Market settlement errors
+            ExchangeError::NotValidMarketResult => {
+                msg!("Feed result not valid when settling market")
+            }
+
+            // Market commence errors
+            ExchangeError::MarketCommenced => msg!("Market has already commenced"),
+
+            // Initialized errors
+            ExchangeError::BettingPoolStateNotInitialized => {
+                msg!("HP liquidity not initialized");
+            }
+            ExchangeError::BettingPoolStateAlreadyInitialized => {
+                msg!("HP liquidity already initialized")
+            }
+            ExchangeError::MarketNotInitialized => msg!("Market not initialized"),
+            ExchangeError::MarketAlreadyInitialized => msg!("Market already initialized"),
+            ExchangeError::BetAlreadyInitialized => msg!("Bet already initialized"),
+            ExchangeError::FeedNotInitialized => msg!("Feed not initialized"),
+
+            // Assertion errors
+            ExchangeError::MarketSideRiskUnderflow => msg!("Market side risk underflow."),
+            ExchangeError::MarketSidePayoutUnderflow => msg!("Market side payout underflow."),
+            ExchangeError::MarketSideRiskRemaining => {
+                msg!("All bets in market settled and market side risk is positive.")
+            }
+            ExchangeError::MarketSidePayoutRemaining => {
+                msg!("All bets in market settled and market side payout is positive.")
+            }
+            ExchangeError::MarketBettorBalanceRemaining => {
+                msg!("All bets in market settled and market bettor balance is positive.")
+            }
+            ExchangeError::HousePoolBettorBalanceRemaining => {
+                msg!("All bets settled and house pool bettor balance is positive.")
+            }
+            ExchangeError::HousePoolLockedLiquidityRemaining => {
+                msg!("The balance in the house pool does not equal available liquidity.")
+            }
+            ExchangeError::HousePoolLiveLiquidityRemaining => {
+                msg!("All bets settled and the live liquidity in the house pool is positive.")
+            }
+
+
+            // Betting init errors
+            ExchangeError::OddsSlippageExceeded => {
+                msg!("Feed odds moved beyond the accepted slippage")
+            }
+
+            // Initialized errors
+            ExchangeError::HpLiquidityNotInitialized => {
+                msg!("House pool state not initialized")
+            }
+            ExchangeError::HpLiquidityAlreadyInitialized => {
+                msg!("House pool state already initialized")
+            }
+
+            // Market void errors
+            ExchangeError::MarketVoided => msg!("Market voided"),
+            ExchangeError::MarketNotVoided => msg!("Market not voided"),
+
+            // Market commence errors
+            ExchangeError::MarketStarted => msg!("Market start time has passed"),
+
+            // Betting init errors
+            ExchangeError::InvalidMarketSide => msg!("Invalid market side"),
+
+            // Market void errors
+            ExchangeError::MarketBetsPending => msg!("Market has unsettled bets"),
+
+            // Market status errors
+            ExchangeError::InvalidMarketStatus => {
+                msg!("Instruction not allowed in the current market status")
+            }
+
+            // Betting init errors
+            ExchangeError::InvalidMarketMetadata => msg!("Invalid market metadata"),
+
+            // Market settlement errors
+            ExchangeError::DisputeWindowOpen => {
+                msg!("Market result is still in its dispute window")
+            }
+            ExchangeError::DisputeWindowClosed => {
+                msg!("Market result dispute window has closed")
+            }
+            ExchangeError::OracleQuorumNotReached => {
+                msg!("Result feeds disagree and no quorum was reached")
+            }
+            ExchangeError::InvalidOracleQuorum => {
+                msg!("Result quorum must be a majority of the registered result feeds")
+            }
+
+            // Oracle errors
+            ExchangeError::StaleFeed => msg!("Feed round is older than the market allows"),
+            ExchangeError::InsufficientOracleResponses => {
+                msg!("Feed round has fewer oracle responses than the market requires")
+            }
+
+            // Market settlement errors
+            ExchangeError::ManualSettlementTooEarly => {
+                msg!("Manual settlement grace period has not passed")
+            }
+
+            // Betting init errors
+            ExchangeError::StakeBelowMinimum => {
+                msg!("Bet risk is below the market minimum stake")
+            }
+            ExchangeError::StakeAboveMaximum => {
+                msg!("Bet risk is above the market maximum stake")
+            }
+            ExchangeError::UserExposureExceeded => {
+                msg!("Bet takes the user's payout on the market above its cap")
+            }
+            ExchangeError::InvalidUserExposureAccount => msg!("Invalid user exposure account"),
+            ExchangeError::MarketLiquidityCeilingExceeded => {
+                msg!("Bet takes the market's locked liquidity above its share of the house pool")
+            }
+            ExchangeError::EventLiquidityCeilingExceeded => {
+                msg!("Bet takes the event's locked liquidity above its share of the house pool")
+            }
+            ExchangeError::InvalidEventGroupAccount => msg!("Invalid event group account"),
+
+            // Market commence errors
+            ExchangeError::MarketSuspended => msg!("Betting on this market is suspended"),
+
+            // Live betting errors
+            ExchangeError::LiveBetNotPending => msg!("Bet is not waiting for acceptance"),
+            ExchangeError::LiveBetAcceptanceDelay => {
+                msg!("Live bet acceptance delay has not passed")
+            }
+
+            // Betting init errors
+            ExchangeError::LineMismatch => {
+                msg!("Bet line is outside the tolerance of the points feed")
+            }
+            ExchangeError::InvalidBetLine => msg!("Bet line is not valid for the bet type"),
+
+            // Parlay errors
+            ExchangeError::InvalidParlayLegCount => msg!("Invalid number of parlay legs"),
+            ExchangeError::DuplicateParlayMarket => {
+                msg!("Parlay legs must be on different markets")
+            }
+            ExchangeError::InvalidParlayAccount => msg!("Invalid parlay account"),
+            ExchangeError::InvalidTeaserLeg => msg!("Teaser legs must be spread or total bets"),
+            ExchangeError::InvalidTeaserConfigAccount => msg!("Invalid teaser config account"),
+            ExchangeError::InvalidTeaserConfig => msg!("Teaser points or odds table not valid"),
+
+            // Cash out errors
+            ExchangeError::CashOutNotAvailable => msg!("Bet cannot be cashed out"),
+        }
+    }
+}
+
+impl From<ExchangeError> for ProgramError {
+    fn from(e: ExchangeError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+impl<T> DecodeError<T> for ExchangeError {
+    fn type_of() -> &'static str {
+        "ExchangeError"
+    }
+}
+
+/// Errors of the divvy house program. They keep the codes the house program
+/// had before its errors moved here, so append new ones at the end.
+#[derive(Error, Debug, Copy, Clone, DeriveFromPrimitive, PartialEq, Eq)]
+pub enum HouseError {
+    /// Invalid instruction
+    #[error("Invalid Instruction")]
+    InvalidInstruction,
+    /// Not Valid Authority
+    #[error("Not Valid Authority")]
+    NotValidAuthority,
+    /// Expected Amount Mismatch
+    #[error("Expected Amount Mismatch")]
+    ExpectedAmountMismatch,
+    /// Expected Data Mismatch
+    #[error("Expected Data Mismatch")]
+    ExpectedDataMismatch,
+    /// Amount Overflow
+    #[error("Amount Overflow")]
+    AmountOverflow,
+    /// Invalid feed account
+    #[error("Invalid feed account")]
+    InvalidFeedAccount,
+    #[error("Invalid house token mint account")]
+    InvalidHtMintAccount,
+    #[error("Invalid house pool USDT account")]
+    InvalidPoolUsdtAccount,
+    #[error("Invalid market account")]
+    InvalidMarketAccount,
+    #[error("Invalid insurance fund USDT account")]
+    InvalidInsuranceFundUsdtAccount,
+    #[error("Invalid divvy foundation USDT account")]
+    InvalidDivvyFoundationUsdtAccount,
+
+    // Deposit withdraw errors
+    #[error("Not enough available liquidity for withdrawal")]
+    NotEnoughAvailableLiquidityForWithdrawal,
+    #[error("Can not use the house pool when there are bets placed on live games")]
+    GamesAreLive,
+    #[error("Pool is frozen")]
+    PoolFrozen,
+
+    // Betting errors
+    #[error("Betting is frozen")]
+    BettingFrozen,
+
+    // Already settled errors
+    #[error("Market already settled")]
+    MarketAlreadySettled,
+    #[error("Market not settled")]
+    MarketNotSettled,
+    #[error("Bet already settled")]
+    BetAlreadySettled,
+
+    // Betting init errors
+    #[error("Not enough available liquidity for bet")]
+    NotEnoughAvailableLiquidityForBet,
+    #[error("Bet risk is zero")]
+    BetRiskZero,
+
+    // Market settlement errors
+    #[error("Feed result not valid when settling market")]
+    NotValidMarketResult,
+
+    // Market commence errors
+    #[error("Market has already commenced")]
+    MarketCommenced,
+
+    // Initialized errors
+    #[error("HP liquidity not initialized")]
+    HpLiquidityNotInitialized,
+    #[error("HP liquidity already initialized")]
+    HpLiquidityAlreadyInitialized,
+    #[error("Market not initialized")]
+    MarketNotInitialized,
+    #[error("Market already initialized")]
+    MarketAlreadyInitialized,
+    #[error("Bet already initialized")]
+    BetAlreadyInitialized,
+    #[error("Feed not initialized")]
+    FeedNotInitialized,
+
+    // Assertion errors
+    #[error("Market side risk underflow.")]
+    MarketSideRiskUnderflow,
+    #[error("Market side payout underflow.")]
+    MarketSidePayoutUnderflow,
+    #[error("All bets in market settled and market side risk is positive.")]
+    MarketSideRiskRemaining,
+    #[error("All bets in market settled and market side payout is positive.")]
+    MarketSidePayoutRemaining,
+    #[error("All bets in market settled and market bettor balance is positive.")]
+    MarketBettorBalanceRemaining,
+    #[error("All bets settled and house pool bettor balance is positive.")]
+    HousePoolBettorBalanceRemaining,
+    #[error("All bets settled and the locked liquidity in the house pool is positive.")]
+    HousePoolLockedLiquidityRemaining,
+    #[error("All bets settled and the live liquidity in the house pool is positive.")]
+    HousePoolLiveLiquidityRemaining,
+}
+
+impl PrintProgramError for HouseError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + TraitsFromPrimitive,
+    {
+        match self {
+            HouseError::InvalidInstruction => msg!("Invalid Instruction"),
+            HouseError::NotValidAuthority => msg!("Not Valid Authority"),
+            HouseError::ExpectedAmountMismatch => msg!("Expected Amount Mismatch"),
+            HouseError::ExpectedDataMismatch => msg!("Expected Data Mismatch"),
+            HouseError::AmountOverflow => msg!("Amount Overflow"),
+            HouseError::InvalidFeedAccount => msg!("Invalid feed account"),
+            HouseError::InvalidHtMintAccount => msg!("Invalid house token mint account"),
+            HouseError::InvalidPoolUsdtAccount => msg!("Invalid house pool USDT account"),
+            HouseError::InvalidMarketAccount => msg!("Invalid market account"),
+            HouseError::InvalidInsuranceFundUsdtAccount => {
+                msg!("Invalid insurance fund USDT account")
+            }
+            HouseError::InvalidDivvyFoundationUsdtAccount => {
+                msg!("Invalid divvy foundation USDT account")
+            }
+
+            // Deposit withdraw errors
+            HouseError::NotEnoughAvailableLiquidityForWithdrawal => {
+                msg!("Not enough available liquidity for withdrawal")
+            }
+            HouseError::GamesAreLive => {
+                msg!("Can not use the house pool when there are bets placed on live games")
+            }
+            HouseError::PoolFrozen => msg!("Pool is frozen"),
+
+            // Betting errors
+            HouseError::BettingFrozen => msg!("Betting is frozen"),
+
+            // Settled errors
+            HouseError::MarketAlreadySettled => msg!("Market already settled"),
+            HouseError::MarketNotSettled => msg!("Market not settled"),
+            HouseError::BetAlreadySettled => msg!("Bet already settled"),
+
+            // Betting init errors
+            HouseError::NotEnoughAvailableLiquidityForBet => {
+                msg!("Not enough available liquidity for bet")
+            }
+            HouseError::BetRiskZero => msg!("Bet risk is zero"),
+
+            // Market settlement errors
+            HouseError::NotValidMarketResult => {
+                msg!("Feed result not valid when settling market")
+            }
+
+            // Market commence errors
+            HouseError::MarketCommenced => msg!("Market has already commenced"),
+
+            // Initialized errors
+            HouseError::HpLiquidityNotInitialized => {
+                msg!("HP liquidity not initialized");
+            }
+            HouseError::HpLiquidityAlreadyInitialized => {
+                msg!("HP liquidity already initialized")
+            }
+            HouseError::MarketNotInitialized => msg!("Market not initialized"),
+            HouseError::MarketAlreadyInitialized => msg!("Market already initialized"),
+            HouseError::BetAlreadyInitialized => msg!("Bet already initialized"),
+            HouseError::FeedNotInitialized => msg!("Feed not initialized"),
+
+            // Assertion errors
+            HouseError::MarketSideRiskUnderflow => msg!("Market side risk underflow."),
+            HouseError::MarketSidePayoutUnderflow => msg!("Market side payout underflow."),
+            HouseError::MarketSideRiskRemaining => {
+                msg!("All bets in market settled and market side risk is positive.")
+            }
+            HouseError::MarketSidePayoutRemaining => {
+                msg!("All bets in market settled and market side payout is positive.")
+            }
+            HouseError::MarketBettorBalanceRemaining => {
+                msg!("All bets in market settled and market bettor balance is positive.")
+            }
+            HouseError::HousePoolBettorBalanceRemaining => {
+                msg!("All bets settled and house pool bettor balance is positive.")
+            }
+            HouseError::HousePoolLockedLiquidityRemaining => {
+                msg!("The balance in the house pool does not equal available liquidity.")
+            }
+            HouseError::HousePoolLiveLiquidityRemaining => {
+                msg!("All bets settled and the live liquidity in the house pool is positive.")
+            }
+        }
+    }
+}
+
+impl From<HouseError> for ProgramError {
+    fn from(e: HouseError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+impl<T> DecodeError<T> for HouseError {
+    fn type_of() -> &'static str {
+        "HouseError"
+    }
+}
@@ -0,0 +1,6 @@
+//! Account layouts, program ids and errors shared by the divvy betting and
+//! divvy house programs.
+
+pub mod error;
+pub mod schema;
+pub mod state;
@@ -8,6 +8,11 @@ pub mod divvy_house_program_id {
     declare_id!("AGetrKU8hVdHEEzisekqPuer1ALHLG2jp5RkgTWKs2hC");
 }
 
+pub mod divvy_betting_program_id {
+    use solana_program::declare_id;
+    declare_id!("GWYmzg8M2QBH1ShezcQuFNhtxHhssSMCRrNviLs6wQyL");
+}
+
 pub mod token_program_id {
     use solana_program::declare_id;
     declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
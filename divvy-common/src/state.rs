@@ -0,0 +1,208 @@
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+
+/// Kind of account, stored in the first byte of the accounts the betting
+/// program creates so an instruction can tell them apart without relying
+/// on their size.
+///
+/// Accounts written before the tag existed store their `is_initialized`
+/// flag there, which reads as `Legacy`. A layout change takes a new tag, and
+/// the old one stays readable.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountType {
+    Uninitialized = 0,
+    Legacy = 1,
+    Market = 2,
+    FuturesMarket = 3,
+    Bet = 4,
+    Parlay = 5,
+    UserExposure = 6,
+    EventGroup = 7,
+    TeaserConfig = 8,
+    BettingPoolState = 9,
+}
+
+impl AccountType {
+    /// Reads the tag of an account from its data.
+    pub fn of(data: &[u8]) -> Result<Self, ProgramError> {
+        Ok(match data.first().ok_or(ProgramError::InvalidAccountData)? {
+            0 => AccountType::Uninitialized,
+            1 => AccountType::Legacy,
+            2 => AccountType::Market,
+            3 => AccountType::FuturesMarket,
+            4 => AccountType::Bet,
+            5 => AccountType::Parlay,
+            6 => AccountType::UserExposure,
+            7 => AccountType::EventGroup,
+            8 => AccountType::TeaserConfig,
+            9 => AccountType::BettingPoolState,
+            _ => return Err(ProgramError::InvalidAccountData),
+        })
+    }
+
+    /// Reads the tag byte of an account that should be of this type, and
+    /// returns whether it is initialized.
+    pub fn unpack_initialized(self, src: &[u8; 1]) -> Result<bool, ProgramError> {
+        match src[0] {
+            0 => Ok(false),
+            tag if tag == self as u8 => Ok(true),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Tag byte for an account of this type.
+    pub fn pack_initialized(self, is_initialized: bool) -> [u8; 1] {
+        if is_initialized {
+            [self as u8]
+        } else {
+            [AccountType::Uninitialized as u8]
+        }
+    }
+}
+
+/// Betting pool state owned by the divvy betting program. The house program
+/// reads it to know how much of its liquidity is locked in or live on markets.
+pub struct BettingPoolState {
+    pub is_initialized: bool,
+    pub locked_liquidity: u64,
+    pub live_liquidity: u64,
+    pub pending_bets: u64,
+    pub house_pool_usdt: Pubkey,
+    pub betting_pool_usdt: Pubkey,
+    pub insurance_fund_usdt: Pubkey,
+    pub divvy_foundation_proceeds_usdt: Pubkey,
+    pub frozen_betting: bool,
+    /// Largest share, in basis points of the house liquidity, a single market may lock.
+    pub max_market_liquidity_bps: u16,
+    /// Largest share, in basis points of the house liquidity, the markets of
+    /// a single event may lock together.
+    pub max_event_liquidity_bps: u16,
+}
+
+impl BettingPoolState {
+    /// Size of the betting pool state account.
+    pub const LEN: usize = 158;
+    /// Size of a betting pool state account created before the liquidity
+    /// ceilings. Its layout is the first `LEGACY_LEN` bytes of the current
+    /// one, tagged `AccountType::Legacy`, and it runs without ceilings until
+    /// `MigrateBettingPool` moves it to an account of the current size.
+    pub const LEGACY_LEN: usize = 154;
+    /// Ceiling that leaves the whole house liquidity available.
+    pub const NO_LIQUIDITY_CEILING_BPS: u16 = 10_000;
+
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let pool_state = BettingPoolState::unpack_unchecked(input)?;
+        if !pool_state.is_initialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        Ok(pool_state)
+    }
+
+    /// Reads the pool state in either layout, picked by the account size.
+    pub fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        match input.len() {
+            BettingPoolState::LEN => {
+                let src = array_ref![input, 0, BettingPoolState::LEN];
+                let (base, max_market_liquidity_bps, max_event_liquidity_bps) =
+                    array_refs![src, BettingPoolState::LEGACY_LEN, 2, 2];
+                Ok(BettingPoolState {
+                    max_market_liquidity_bps: u16::from_le_bytes(*max_market_liquidity_bps),
+                    max_event_liquidity_bps: u16::from_le_bytes(*max_event_liquidity_bps),
+                    ..BettingPoolState::unpack_base(base, AccountType::BettingPoolState)?
+                })
+            }
+            BettingPoolState::LEGACY_LEN => BettingPoolState::unpack_base(
+                array_ref![input, 0, BettingPoolState::LEGACY_LEN],
+                AccountType::Legacy,
+            ),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Writes the pool state in the layout of the account it is stored in. A
+    /// legacy account only takes a pool state without liquidity ceilings.
+    pub fn pack(src: BettingPoolState, dst: &mut [u8]) -> Result<(), ProgramError> {
+        match dst.len() {
+            BettingPoolState::LEN => {
+                let dst = array_mut_ref![dst, 0, BettingPoolState::LEN];
+                let (base_dst, max_market_liquidity_bps_dst, max_event_liquidity_bps_dst) =
+                    mut_array_refs![dst, BettingPoolState::LEGACY_LEN, 2, 2];
+                src.pack_base(base_dst, AccountType::BettingPoolState);
+                *max_market_liquidity_bps_dst = src.max_market_liquidity_bps.to_le_bytes();
+                *max_event_liquidity_bps_dst = src.max_event_liquidity_bps.to_le_bytes();
+                Ok(())
+            }
+            BettingPoolState::LEGACY_LEN => {
+                if src.max_market_liquidity_bps != BettingPoolState::NO_LIQUIDITY_CEILING_BPS
+                    || src.max_event_liquidity_bps != BettingPoolState::NO_LIQUIDITY_CEILING_BPS
+                {
+                    msg!("Liquidity ceilings need a migrated betting pool state");
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                src.pack_base(
+                    array_mut_ref![dst, 0, BettingPoolState::LEGACY_LEN],
+                    AccountType::Legacy,
+                );
+                Ok(())
+            }
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    fn unpack_base(
+        src: &[u8; BettingPoolState::LEGACY_LEN],
+        account_type: AccountType,
+    ) -> Result<Self, ProgramError> {
+        let (
+            is_initialized,
+            locked_liquidity,
+            live_liquidity,
+            pending_bets,
+            house_pool_usdt,
+            betting_pool_usdt,
+            insurance_fund_usdt,
+            divvy_foundation_proceeds_usdt,
+            frozen_betting,
+        ) = array_refs![src, 1, 8, 8, 8, 32, 32, 32, 32, 1];
+
+        Ok(BettingPoolState {
+            is_initialized: account_type.unpack_initialized(is_initialized)?,
+            locked_liquidity: u64::from_le_bytes(*locked_liquidity),
+            live_liquidity: u64::from_le_bytes(*live_liquidity),
+            pending_bets: u64::from_le_bytes(*pending_bets),
+            house_pool_usdt: Pubkey::new_from_array(*house_pool_usdt),
+            betting_pool_usdt: Pubkey::new_from_array(*betting_pool_usdt),
+            insurance_fund_usdt: Pubkey::new_from_array(*insurance_fund_usdt),
+            divvy_foundation_proceeds_usdt: Pubkey::new_from_array(*divvy_foundation_proceeds_usdt),
+            frozen_betting: frozen_betting[0] != 0,
+            max_market_liquidity_bps: BettingPoolState::NO_LIQUIDITY_CEILING_BPS,
+            max_event_liquidity_bps: BettingPoolState::NO_LIQUIDITY_CEILING_BPS,
+        })
+    }
+
+    fn pack_base(&self, dst: &mut [u8; BettingPoolState::LEGACY_LEN], account_type: AccountType) {
+        let (
+            is_initialized_dst,
+            locked_liquidity_dst,
+            live_liquidity_dst,
+            pending_bets_dst,
+            house_pool_usdt_dst,
+            betting_pool_usdt_dst,
+            insurance_fund_usdt_dst,
+            divvy_foundation_proceeds_usdt_dst,
+            frozen_betting_dst,
+        ) = mut_array_refs![dst, 1, 8, 8, 8, 32, 32, 32, 32, 1];
+
+        *is_initialized_dst = account_type.pack_initialized(self.is_initialized);
+        *locked_liquidity_dst = self.locked_liquidity.to_le_bytes();
+        *live_liquidity_dst = self.live_liquidity.to_le_bytes();
+        *pending_bets_dst = self.pending_bets.to_le_bytes();
+        house_pool_usdt_dst.copy_from_slice(self.house_pool_usdt.as_ref());
+        betting_pool_usdt_dst.copy_from_slice(self.betting_pool_usdt.as_ref());
+        insurance_fund_usdt_dst.copy_from_slice(self.insurance_fund_usdt.as_ref());
+        divvy_foundation_proceeds_usdt_dst.copy_from_slice(self.divvy_foundation_proceeds_usdt.as_ref());
+        frozen_betting_dst[0] = self.frozen_betting as u8;
+    }
+}
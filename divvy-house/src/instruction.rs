@@ -1,10 +1,16 @@
-use solana_program::program_error::ProgramError;
-use std::{convert::TryInto};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::{convert::TryInto, mem::size_of};
 
 use crate::{
-    error::ExchangeError::{self, InvalidInstruction},
+    error::HouseError::{self, InvalidInstruction},
+    schema::token_program_id,
 };
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum HouseInstruction {
     Deposit {
         /// The amount party A expects to receive of token Y
@@ -47,7 +53,7 @@ impl HouseInstruction {
             3 => {
                 let (freeze_pool, rest) = rest
                     .split_first()
-                    .ok_or(ExchangeError::InvalidInstruction)?;
+                    .ok_or(HouseError::InvalidInstruction)?;
                 Self::Freeze {
                     freeze_pool: *freeze_pool != 0,
                 }
@@ -60,6 +66,45 @@ impl HouseInstruction {
         })
     }
 
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(size_of::<Self>());
+        match *self {
+            Self::Deposit {
+                usdt_amount,
+                bump_seed,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&usdt_amount.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::Withdraw {
+                ht_amount,
+                bump_seed,
+            } => {
+                buf.push(1);
+                buf.extend_from_slice(&ht_amount.to_le_bytes());
+                buf.push(bump_seed);
+            }
+            Self::Ownership { bump_seed } => {
+                buf.push(2);
+                buf.push(bump_seed);
+            }
+            Self::Freeze { freeze_pool } => {
+                buf.push(3);
+                buf.push(freeze_pool as u8);
+            }
+            Self::TransferLockedLiquidity {
+                usdt_amount,
+                bump_seed,
+            } => {
+                buf.push(4);
+                buf.extend_from_slice(&usdt_amount.to_le_bytes());
+                buf.push(bump_seed);
+            }
+        };
+        buf
+    }
+
     // Todo: delete these 4 methods and use split_first, like in spl-token/instruction.rs
     fn unpack_last(input: &[u8]) -> Result<u8, ProgramError> {
         let (last, _rest) = input.split_last().ok_or(InvalidInstruction)?;
@@ -75,3 +120,188 @@ impl HouseInstruction {
     }
    
 }
+
+/// Creates a `Deposit` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    program_id: &Pubkey,
+    user_account: &Pubkey,
+    ht_mint_account: &Pubkey,
+    user_ht_account: &Pubkey,
+    pda_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    usdt_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Deposit {
+        usdt_amount,
+        bump_seed,
+    }
+    .pack();
+    let accounts = liquidity_accounts(
+        user_account,
+        ht_mint_account,
+        user_ht_account,
+        pda_account,
+        user_usdt_account,
+        pool_usdt_account,
+        pool_state_account,
+        bet_pool_state_account,
+    );
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Withdraw` instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw(
+    program_id: &Pubkey,
+    user_account: &Pubkey,
+    ht_mint_account: &Pubkey,
+    user_ht_account: &Pubkey,
+    pda_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+    ht_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Withdraw {
+        ht_amount,
+        bump_seed,
+    }
+    .pack();
+    let accounts = liquidity_accounts(
+        user_account,
+        ht_mint_account,
+        user_ht_account,
+        pda_account,
+        user_usdt_account,
+        pool_usdt_account,
+        pool_state_account,
+        bet_pool_state_account,
+    );
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn liquidity_accounts(
+    user_account: &Pubkey,
+    ht_mint_account: &Pubkey,
+    user_ht_account: &Pubkey,
+    pda_account: &Pubkey,
+    user_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state_account: &Pubkey,
+    bet_pool_state_account: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*user_account, true),
+        AccountMeta::new(*ht_mint_account, false),
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*user_ht_account, false),
+        AccountMeta::new_readonly(*pda_account, false),
+        AccountMeta::new(*user_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new_readonly(*pool_state_account, false),
+        AccountMeta::new_readonly(*bet_pool_state_account, false),
+    ]
+}
+
+/// Creates an `Ownership` instruction that initializes the house pool state.
+pub fn ownership(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state_account: &Pubkey,
+    ht_mint_account: &Pubkey,
+    betting_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Ownership { bump_seed }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*pool_state_account, false),
+        AccountMeta::new_readonly(*ht_mint_account, false),
+        AccountMeta::new_readonly(*betting_usdt_account, false),
+        AccountMeta::new_readonly(*pool_usdt_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Freeze` instruction.
+pub fn freeze(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    pool_state_account: &Pubkey,
+    freeze_pool: bool,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::Freeze { freeze_pool }.pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `TransferLockedLiquidity` instruction.
+///
+/// Only the betting program can sign for `bet_pda_account`, so this is
+/// meant to be invoked through a CPI from the betting program.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_locked_liquidity(
+    program_id: &Pubkey,
+    pda_account: &Pubkey,
+    bet_pda_account: &Pubkey,
+    betting_usdt_account: &Pubkey,
+    pool_usdt_account: &Pubkey,
+    pool_state_account: &Pubkey,
+    usdt_amount: u64,
+    bump_seed: u8,
+) -> Result<Instruction, ProgramError> {
+    let data = HouseInstruction::TransferLockedLiquidity {
+        usdt_amount,
+        bump_seed,
+    }
+    .pack();
+
+    let accounts = vec![
+        AccountMeta::new_readonly(token_program_id::ID, false),
+        AccountMeta::new(*pda_account, false),
+        AccountMeta::new_readonly(*bet_pda_account, true),
+        AccountMeta::new(*betting_usdt_account, false),
+        AccountMeta::new(*pool_usdt_account, false),
+        AccountMeta::new(*pool_state_account, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
@@ -1,13 +1,13 @@
-use error::ExchangeError;
+use error::HouseError;
 use spl_token::state::Account as TokenAccount;
 use state::{BettingPoolState};
 
-pub mod error;
 pub mod instruction;
 pub mod processor;
-pub mod schema;
 pub mod state;
 
+pub use divvycommon::{error, schema};
+
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 
@@ -16,7 +16,7 @@ pub mod entrypoint;
 fn calculate_available_liquidity(
     pool_usdt_state: &TokenAccount,
     bet_pool_state: &BettingPoolState,
-) -> Result<u64, ExchangeError> {
+) -> Result<u64, HouseError> {
     let available_liquidity = pool_usdt_state
         .amount - bet_pool_state.locked_liquidity;
     return Ok(available_liquidity);
@@ -18,7 +18,7 @@ use spl_token::{
 
 use crate::{
     calculate_available_liquidity,
-    error::ExchangeError,
+    error::HouseError,
     instruction::HouseInstruction,
     schema::{authority, token_program_id},
     state::{HpLiquidity, BettingPoolState},
@@ -100,13 +100,13 @@ impl Processor {
 
         // Checking house token ownership
         if *ht_mint_account.key != pool_state.ht_mint {
-            return Err(ExchangeError::InvalidHtMintAccount.into());
+            return Err(HouseError::InvalidHtMintAccount.into());
         }
         if *pool_usdt_account.key != pool_state.pool_usdt {
-            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+            return Err(HouseError::InvalidPoolUsdtAccount.into());
         }
         if *token_program.key != token_program_id::ID {
-            return Err(ExchangeError::InvalidInstruction.into());
+            return Err(HouseError::InvalidInstruction.into());
         }
 
         msg!("- USDT amount deposited");
@@ -122,11 +122,11 @@ impl Processor {
         msg!(0, 0, 0, 0, bet_pool_state.live_liquidity);
 
         if bet_pool_state.live_liquidity > 0 {
-            return Err(ExchangeError::GamesAreLive.into());
+            return Err(HouseError::GamesAreLive.into());
         }
 
         if pool_state.frozen_pool {
-            return Err(ExchangeError::PoolFrozen.into());
+            return Err(HouseError::PoolFrozen.into());
         }
         // TODO Add checked math 
         let ht_amount = match ht_mint_state.supply {
@@ -136,9 +136,9 @@ impl Processor {
                     pool_usdt_state
                         .amount + bet_pool_state.locked_liquidity + bet_pool_state.live_liquidity
                 ))
-                .ok_or(ExchangeError::AmountOverflow)?
+                .ok_or(HouseError::AmountOverflow)?
                 .checked_mul(U64F64::from_num(usdt_amount))
-                .ok_or(ExchangeError::AmountOverflow)?)
+                .ok_or(HouseError::AmountOverflow)?)
             .to_num(),
         };
 
@@ -213,13 +213,13 @@ impl Processor {
 
         // Checking house token ownership
         if *ht_mint_account.key != pool_state.ht_mint {
-            return Err(ExchangeError::InvalidHtMintAccount.into());
+            return Err(HouseError::InvalidHtMintAccount.into());
         }
         if *pool_usdt_account.key != pool_state.pool_usdt {
-            return Err(ExchangeError::InvalidPoolUsdtAccount.into());
+            return Err(HouseError::InvalidPoolUsdtAccount.into());
         }
         if *token_program.key != token_program_id::ID {
-            return Err(ExchangeError::InvalidInstruction.into());
+            return Err(HouseError::InvalidInstruction.into());
         }
 
         msg!("- HT amount burned");
@@ -234,10 +234,10 @@ impl Processor {
         msg!(0, 0, 0, 0, bet_pool_state.live_liquidity);
 
         if bet_pool_state.live_liquidity > 0 {
-            return Err(ExchangeError::GamesAreLive.into());
+            return Err(HouseError::GamesAreLive.into());
         }
         if pool_state.frozen_pool {
-            return Err(ExchangeError::PoolFrozen.into());
+            return Err(HouseError::PoolFrozen.into());
         }
 
         let usdt_amount: u64 = (U64F64::from_num(
@@ -245,9 +245,9 @@ impl Processor {
                 .amount + bet_pool_state.locked_liquidity + bet_pool_state.live_liquidity
         )
         .checked_div(U64F64::from_num(ht_mint_state.supply))
-        .ok_or(ExchangeError::AmountOverflow)?
+        .ok_or(HouseError::AmountOverflow)?
         .checked_mul(U64F64::from_num(ht_amount))
-        .ok_or(ExchangeError::AmountOverflow)?)
+        .ok_or(HouseError::AmountOverflow)?)
         .to_num();
         let available_liquidity = calculate_available_liquidity(&pool_usdt_state, &bet_pool_state)?;
 
@@ -257,7 +257,7 @@ impl Processor {
         msg!(0, 0, 0, 0, usdt_amount);
 
         if usdt_amount > available_liquidity {
-            return Err(ExchangeError::NotEnoughAvailableLiquidityForWithdrawal.into());
+            return Err(HouseError::NotEnoughAvailableLiquidityForWithdrawal.into());
         }
 
         msg!("Burning HT");
@@ -368,7 +368,7 @@ impl Processor {
         msg!("Check HP State Init");
         // Doesn't help, should change
         // if pool_state.is_initialized {
-        //     return Err(ExchangeError::HpLiquidityAlreadyInitialized.into());
+        //     return Err(HouseError::HpLiquidityAlreadyInitialized.into());
         // }
         msg!("Check Rent Exemption");
         if !Rent::get()?.is_exempt(
@@ -385,7 +385,7 @@ impl Processor {
 
         msg!("Check authority");
         if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
+            return Err(HouseError::NotValidAuthority.into());
         }
 
         msg!("Initalizing HP State account");
@@ -413,7 +413,7 @@ impl Processor {
             return Err(ProgramError::MissingRequiredSignature);
         }
         if initializer.key != &authority::ID {
-            return Err(ExchangeError::NotValidAuthority.into());
+            return Err(HouseError::NotValidAuthority.into());
         }
 
         let mut pool_state = HpLiquidity::unpack(&pool_state_account.data.borrow())?;
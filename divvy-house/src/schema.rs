@@ -1,20 +0,0 @@
-pub mod authority {
-    use solana_program::declare_id;
-    declare_id!("FU82ePUAxyyezYyczDtXNYt1SG2dSE3Yv24MRH1V51hM");
-}
-
-pub mod token_program_id {
-    use solana_program::declare_id;
-    declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
-}
-
-pub mod switchboard_devnet_id {
-    use solana_program::declare_id;
-    declare_id!("7azgmy1pFXHikv36q1zZASvFq5vFa39TT9NweVugKKTU");
-}
-
-
-pub mod switchboard_mainnet_id {
-    use solana_program::declare_id;
-    declare_id!("DtmE9D2CSB4L5D6A15mraeEjrGMm6auWVzgaD8hK2tZM");
-}
\ No newline at end of file
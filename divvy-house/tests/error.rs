@@ -0,0 +1,22 @@
+use divvyhouse::error::HouseError;
+use solana_program::program_error::ProgramError;
+
+/// Clients match on these codes, so they must not move.
+#[test]
+fn error_codes_are_stable() {
+    let cases = [
+        (HouseError::InvalidInstruction, 0),
+        (HouseError::NotValidAuthority, 1),
+        (HouseError::AmountOverflow, 4),
+        (HouseError::InvalidHtMintAccount, 6),
+        (HouseError::InvalidPoolUsdtAccount, 7),
+        (HouseError::NotEnoughAvailableLiquidityForWithdrawal, 11),
+        (HouseError::GamesAreLive, 12),
+        (HouseError::PoolFrozen, 13),
+        (HouseError::HpLiquidityNotInitialized, 22),
+        (HouseError::HpLiquidityAlreadyInitialized, 23),
+    ];
+    for &(error, code) in cases.iter() {
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code), "{:?}", error);
+    }
+}
@@ -0,0 +1,56 @@
+use divvyhouse::instruction::HouseInstruction;
+
+/// One instruction of every variant, with fields away from their defaults so
+/// a field packed at the wrong offset shows up.
+fn instructions() -> Vec<HouseInstruction> {
+    vec![
+        HouseInstruction::Deposit {
+            usdt_amount: 1_000_000,
+            bump_seed: 251,
+        },
+        HouseInstruction::Withdraw {
+            ht_amount: 750_000,
+            bump_seed: 251,
+        },
+        HouseInstruction::Ownership { bump_seed: 251 },
+        HouseInstruction::Freeze { freeze_pool: true },
+        HouseInstruction::TransferLockedLiquidity {
+            usdt_amount: 2_500_000,
+            bump_seed: 251,
+        },
+    ]
+}
+
+#[test]
+fn every_instruction_round_trips() {
+    for instruction in instructions() {
+        let packed = instruction.pack();
+        assert_eq!(
+            HouseInstruction::unpack(&packed).unwrap(),
+            instruction,
+            "packed as {:?}",
+            packed
+        );
+    }
+}
+
+#[test]
+fn every_tag_is_covered() {
+    let tags: Vec<u8> = instructions().iter().map(|instruction| instruction.pack()[0]).collect();
+    let mut sorted = tags.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..tags.len() as u8).collect::<Vec<_>>());
+    assert!(HouseInstruction::unpack(&[tags.len() as u8]).is_err());
+}
+
+#[test]
+fn truncated_instructions_are_rejected() {
+    for instruction in instructions() {
+        let packed = instruction.pack();
+        assert!(
+            HouseInstruction::unpack(&packed[..1]).is_err(),
+            "{:?} without its data",
+            instruction
+        );
+    }
+}